@@ -0,0 +1,167 @@
+use std::{fs, io, path::Path};
+
+pub const BARREL_FILE: &str = "index.ts";
+
+/// The module specifier of `file` relative to its directory, `./user.entity`
+/// for `user.entity.ts`. Unset for files that are not re-exported.
+fn specifier(file: &Path) -> Option<String> {
+    let name = file.file_name()?.to_str()?;
+    if name == BARREL_FILE {
+        return None;
+    }
+
+    let stem = name
+        .strip_suffix(".ts")
+        .or_else(|| name.strip_suffix(".tsx"))?;
+    Some(format!("./{}", stem))
+}
+
+/// The specifier of an `export * from '...'` line.
+fn exported(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix("export * from ")?;
+    let rest = rest.trim_end_matches(';');
+    rest.strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+        .or_else(|| rest.strip_prefix('"')?.strip_suffix('"'))
+}
+
+fn write_barrel(path: &Path, lines: &[String]) -> io::Result<()> {
+    if lines.iter().all(|line| line.trim().is_empty()) {
+        return match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+
+    fs::write(path, lines.join("\n") + "\n")
+}
+
+fn read_lines(path: &Path) -> io::Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content.lines().map(str::to_string).collect()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Adds an export of `file` to the `index.ts` next to it. The export goes
+/// before the first existing export that sorts after it, following the quote
+/// and semicolon style of the existing ones, and other lines are left alone.
+pub fn add_export(file: &Path) -> io::Result<()> {
+    let (Some(dir), Some(specifier)) = (file.parent(), specifier(file)) else {
+        return Ok(());
+    };
+    let path = dir.join(BARREL_FILE);
+    let mut lines = read_lines(&path)?;
+
+    let exports: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| Some((index, exported(line)?)))
+        .collect();
+
+    if exports.iter().any(|(_, existing)| *existing == specifier) {
+        return Ok(());
+    }
+
+    let line = match exports.first() {
+        Some((index, _)) => {
+            let template = lines[*index].trim();
+            let quote = if template.contains('"') { '"' } else { '\'' };
+            let semicolon = if template.ends_with(';') { ";" } else { "" };
+            format!("export * from {0}{1}{0}{2}", quote, specifier, semicolon)
+        }
+        None => format!("export * from '{}'", specifier),
+    };
+
+    let position = exports
+        .iter()
+        .find(|(_, existing)| *existing > specifier.as_str())
+        .map(|(index, _)| *index)
+        .or_else(|| exports.last().map(|(index, _)| index + 1))
+        .unwrap_or(lines.len());
+
+    lines.insert(position, line);
+    write_barrel(&path, &lines)
+}
+
+/// Removes the export of `file` from the `index.ts` next to it, deleting the
+/// barrel once nothing is left in it.
+pub fn remove_export(file: &Path) -> io::Result<()> {
+    let (Some(dir), Some(specifier)) = (file.parent(), specifier(file)) else {
+        return Ok(());
+    };
+    let path = dir.join(BARREL_FILE);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let mut lines = read_lines(&path)?;
+    let count = lines.len();
+    lines.retain(|line| exported(line) != Some(specifier.as_str()));
+
+    if lines.len() == count {
+        return Ok(());
+    }
+
+    write_barrel(&path, &lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    /// A fresh directory holding `index.ts` with `barrel`, unless it is unset.
+    fn barrel_dir(name: &str, barrel: Option<&str>) -> TempDir {
+        match barrel {
+            Some(barrel) => TempDir::new(name, &[(BARREL_FILE, barrel)]),
+            None => TempDir::new(name, &[]),
+        }
+    }
+
+    fn barrel(dir: &Path) -> Option<String> {
+        fs::read_to_string(dir.join(BARREL_FILE)).ok()
+    }
+
+    #[test]
+    fn creates_the_barrel() {
+        let dir = barrel_dir("barrel-create", None);
+        add_export(&dir.join("user.entity.ts")).unwrap();
+        add_export(&dir.join("user.entity.ts")).unwrap();
+        add_export(&dir.join(BARREL_FILE)).unwrap();
+
+        assert_eq!(barrel(&dir).unwrap(), "export * from './user.entity'\n");
+    }
+
+    #[test]
+    fn inserts_in_order_following_the_existing_style() {
+        let dir = barrel_dir(
+            "barrel-order",
+            Some("// shared\nexport * from \"./a.entity\";\nexport * from \"./c.entity\";\nexport { x } from './x'\n"),
+        );
+        add_export(&dir.join("b.entity.ts")).unwrap();
+        add_export(&dir.join("d.entity.ts")).unwrap();
+
+        assert_eq!(
+            barrel(&dir).unwrap(),
+            "// shared\nexport * from \"./a.entity\";\nexport * from \"./b.entity\";\nexport * from \"./c.entity\";\nexport * from \"./d.entity\";\nexport { x } from './x'\n"
+        );
+    }
+
+    #[test]
+    fn removes_exports_and_empty_barrels() {
+        let dir = barrel_dir(
+            "barrel-remove",
+            Some("export * from './a.entity'\nexport * from './b.entity';\n"),
+        );
+        remove_export(&dir.join("a.entity.ts")).unwrap();
+        assert_eq!(barrel(&dir).unwrap(), "export * from './b.entity';\n");
+
+        remove_export(&dir.join("missing.entity.ts")).unwrap();
+        remove_export(&dir.join("b.entity.ts")).unwrap();
+        assert_eq!(barrel(&dir), None);
+
+        remove_export(&dir.join("b.entity.ts")).unwrap();
+    }
+}
@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use crate::code_gen::generate_targets;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::parser::Schema;
+use crate::templates::Templates;
+use crate::tsconfig::TsConfig;
+use crate::writer::{file_status, unified_diff, FileStatus};
+
+/// Regenerates every configured target in memory and reports the generated
+/// files that are missing or out of date.
+pub fn check(dir: &Path, schema: &Schema, ts_config: &TsConfig, config: &Config) -> Result<()> {
+    if config.generate.is_empty() {
+        return Err(Error::Usage(
+            "no `generate` targets configured, nothing to check".to_string(),
+        ));
+    }
+
+    let templates = Templates::new(dir.join(&config.templates_dir));
+    let mut up_to_date = true;
+
+    for file in generate_targets(dir, schema, ts_config, config, &templates)? {
+        match file_status(&file.path, &file.contents).map_err(Error::io(&file.path))? {
+            FileStatus::Unchanged => {}
+            FileStatus::New => {
+                println!("{} (missing)", file.path.display());
+                up_to_date = false;
+            }
+            FileStatus::Changed { existing, merged } => {
+                print!("{}", unified_diff(&file.path, &existing, &merged));
+                up_to_date = false;
+            }
+        }
+    }
+
+    if !up_to_date {
+        return Err(Error::OutOfDate);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use std::{env, path::PathBuf};
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    #[default]
+    Generate,
+    Schema,
+    Check,
+    Prune,
+    Watch,
+}
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub command: Command,
+    pub schema: Option<PathBuf>,
+    /// Output directory replacing the module prompt.
+    pub out: Option<PathBuf>,
+    pub json: bool,
+    pub force: bool,
+    pub dry_run: bool,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(env::args().skip(1))
+    }
+
+    pub fn parse_from<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+
+            match flag.as_str() {
+                "--schema" => {
+                    let value = inline_value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| Error::Usage("--schema expects a path".to_string()))?;
+                    parsed.schema = Some(PathBuf::from(value));
+                }
+                "--out" => {
+                    let value = inline_value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| Error::Usage("--out expects a path".to_string()))?;
+                    parsed.out = Some(PathBuf::from(value));
+                }
+                "--json" => parsed.json = true,
+                "--force" => parsed.force = true,
+                "--dry-run" => parsed.dry_run = true,
+                "schema" if parsed.command == Command::Generate => parsed.command = Command::Schema,
+                "check" if parsed.command == Command::Generate => parsed.command = Command::Check,
+                "prune" if parsed.command == Command::Generate => parsed.command = Command::Prune,
+                "watch" if parsed.command == Command::Generate => parsed.command = Command::Watch,
+                _ => return Err(Error::Usage(format!("unknown argument {}", flag))),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
@@ -1,43 +1,66 @@
-use crate::parser::{Field, Model};
+use crate::barrel::add_export;
+use crate::config::{ArtifactScope, Config, CustomArtifact, GenerateTarget, NamingConfig};
+use crate::error::{Error, Result};
+use crate::manifest::Manifest;
+use crate::parser::{CompositeType, Field, FieldKind, Model, Schema};
+use crate::templates::{
+    Templates, ENTITY_TEMPLATE, MAPPER_TEMPLATE, PRISMA_REPOSITORY_TEMPLATE, REPOSITORY_TEMPLATE,
+    VALUE_OBJECT_TEMPLATE,
+};
+use crate::tsconfig::TsConfig;
+use crate::writer::Writer;
 use core::fmt;
-use std::fmt::Write as FmtWrite;
-use std::io::Write as IoWrite;
-use std::{fs, path::Path};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 
-const ENTITY_PATH: &str = "domain/entity/";
-const MAPPER_PATH: &str = "infra/database/prisma/mappers";
-const REPOSITORY_PATH: &str = "app/repositories";
-const PRISMA_REPOSITORY_PATH: &str = "infra/database/prisma";
-
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ModuleType {
     Entity,
     Mapper,
     Repository(Option<Vec<RepositoryOperations>>),
     PrismaRepository,
+    ValueObject,
+    /// An artifact declared in the project config, by name.
+    Custom(String),
 }
 
-impl From<&str> for ModuleType {
-    fn from(value: &str) -> Self {
-        match value {
-            "Entity" => ModuleType::Entity,
-            "Mapper" => ModuleType::Mapper,
-            "Repository" => ModuleType::Repository(None),
-            "Prisma repository" => ModuleType::PrismaRepository,
-            _ => unreachable!(),
-        }
-    }
-}
-
-impl From<ModuleType> for &str {
-    fn from(value: ModuleType) -> Self {
-        match value {
+impl ModuleType {
+    pub fn label(&self) -> &str {
+        match self {
             ModuleType::Entity => "Entity",
             ModuleType::Mapper => "Mapper",
             ModuleType::Repository(_) => "Repository",
             ModuleType::PrismaRepository => "Prisma repository",
+            ModuleType::ValueObject => "Value object",
+            ModuleType::Custom(name) => name,
         }
     }
+
+    /// Looks up a selectable artifact by its case-insensitive label.
+    pub fn from_name(name: &str, config: &Config) -> Option<ModuleType> {
+        ModuleType::selectable(config)
+            .into_iter()
+            .find(|module| module.label().eq_ignore_ascii_case(name))
+    }
+
+    /// Every artifact that can be picked in the "Select which classes to
+    /// create" prompt: the built-in ones followed by the configured ones.
+    pub fn selectable(config: &Config) -> Vec<ModuleType> {
+        [
+            ModuleType::Entity,
+            ModuleType::Mapper,
+            ModuleType::Repository(None),
+        ]
+        .into_iter()
+        .chain(
+            config
+                .custom_artifacts
+                .iter()
+                .map(|artifact| ModuleType::Custom(artifact.name.clone())),
+        )
+        .collect()
+    }
 }
 
 fn lowercase_first_char(s: &str) -> String {
@@ -48,13 +71,26 @@ fn lowercase_first_char(s: &str) -> String {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum RepositoryOperations {
     Create,
     Find,
     FindMany,
     Delete,
     Update,
+    Count,
+}
+
+impl RepositoryOperations {
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            RepositoryOperations::Find
+                | RepositoryOperations::FindMany
+                | RepositoryOperations::Count
+        )
+    }
 }
 
 impl fmt::Display for RepositoryOperations {
@@ -65,380 +101,315 @@ impl fmt::Display for RepositoryOperations {
             RepositoryOperations::FindMany => write!(f, "findMany"),
             RepositoryOperations::Delete => write!(f, "delete"),
             RepositoryOperations::Update => write!(f, "update"),
+            RepositoryOperations::Count => write!(f, "count"),
         }
     }
 }
 
-fn build_repository_methods(
-    model_name: &str,
-    input_type: &str,
-    return_type: &str,
-    has_mapper: bool,
-    op: &RepositoryOperations,
-) -> String {
-    match op {
-        RepositoryOperations::Create => {
-            let create_input_type = if input_type == "any" {
-                input_type
-            } else {
-                model_name
-            };
-
-            let mut method = format!(
-                "async create(data: {}): Promise<{}> {{\n",
-                create_input_type, return_type
-            );
-            if has_mapper {
-                write!(
-                    method,
-                    r#"    const result = await this.prisma.{}.create({{
-      data,
-    }})
-
-    return {}Mapper.toDomain(result)
-  }}"#,
-                    lowercase_first_char(model_name),
-                    model_name
-                )
-                .unwrap();
-
-                return method;
-            }
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Names {
+    class: String,
+    interface: String,
+    mapper: String,
+    repository: String,
+    prisma_repository: String,
+    camel: String,
+    kebab: String,
+}
 
-            write!(
-                method,
-                r#"      return this.prisma.{}.create({{
-        data,
-      }})
-  }}"#,
-                lowercase_first_char(model_name)
-            )
-            .unwrap();
-
-            method
+impl Names {
+    fn new(name: &str, naming: &NamingConfig) -> Self {
+        Names {
+            class: name.to_string(),
+            interface: naming.interface(name),
+            mapper: naming.mapper(name),
+            repository: naming.repository(name),
+            prisma_repository: naming.prisma_repository(name),
+            camel: lowercase_first_char(name),
+            kebab: to_kebab_case(name),
         }
-        RepositoryOperations::Delete => format!(
-            r#"async delete(id: string) {{
-    await this.prisma.{}.update({{
-      where: {{
-        id,
-      }},
-      data: {{
-        deletedAt: new Date(),
-      }},
-    }})
-  }}"#,
-            lowercase_first_char(model_name)
-        ),
-        RepositoryOperations::Find => {
-            let mut method = format!(
-                "async find(data: {}): Promise<{}> {{\n",
-                input_type, return_type
-            );
+    }
+}
 
-            if has_mapper {
-                write!(
-                    method,
-                    r#"    const result = await this.prisma.{}.findFirst({{
-      where: data,
-    }})
-
-    return {}Mapper.toDomain(result)
-  }}"#,
-                    lowercase_first_char(model_name),
-                    model_name
-                )
-                .unwrap();
-
-                return method;
-            }
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldContext<'a> {
+    #[serde(flatten)]
+    field: &'a Field,
+    /// TypeScript type including the list marker, unset for fields that are
+    /// not part of the domain such as relations.
+    ts_type: Option<String>,
+    /// Expression converting the Prisma value into the domain one.
+    mapper_value: Option<String>,
+}
 
-            write!(
-                method,
-                r#"      return this.prisma.{}.findFirst({{
-        where: data,
-      }})
-  }}"#,
-                lowercase_first_char(model_name)
-            )
-            .unwrap();
-
-            method
+impl<'a> FieldContext<'a> {
+    fn new(field: &'a Field, config: &Config) -> Self {
+        let base_type = resolve_ts_type(field, &config.type_mappings);
+        let ts_type = base_type.map(|ts_type| {
+            if field.is_list {
+                format!("{}[]", ts_type)
+            } else {
+                ts_type.to_string()
+            }
+        });
+
+        let is_number = base_type == Some("number");
+        let mapper_value = match field.field_type.as_str() {
+            _ if base_type.is_none() => None,
+            composite if field.kind == FieldKind::Composite => {
+                Some(match (field.is_list, field.is_optional) {
+                    (true, _) => {
+                        format!("data.{}.map((item) => new {}(item))", field.name, composite)
+                    }
+                    (false, true) => format!(
+                        "data.{} ? new {}(data.{}) : null",
+                        field.name, composite, field.name
+                    ),
+                    (false, false) => format!("new {}(data.{})", composite, field.name),
+                })
+            }
+            "Decimal" | "BigInt" if is_number && field.is_list => {
+                Some(format!("data.{}.map(Number)", field.name))
+            }
+            "Decimal" | "BigInt" if is_number => Some(format!("Number(data.{})", field.name)),
+            _ => Some(format!("data.{}", field.name)),
+        };
+
+        FieldContext {
+            field,
+            ts_type,
+            mapper_value,
         }
-        RepositoryOperations::FindMany => {
-            let mut method = format!(
-                "async findMany(data: {}): Promise<{}[]> {{\n",
-                input_type, return_type
-            );
+    }
+}
 
-            if has_mapper {
-                write!(
-                    method,
-                    r#"    const result = await this.prisma.{}.findMany({{
-      where: data,
-    }})
-
-    return result.map({}Mapper.toDomain)
-  }}"#,
-                    lowercase_first_char(model_name),
-                    model_name
-                )
-                .unwrap();
-
-                return method;
-            }
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryContext {
+    operations: Vec<RepositoryOperations>,
+    input_type: String,
+    create_input_type: String,
+    return_type: String,
+    has_mapper: bool,
+    has_entity: bool,
+}
 
-            write!(
-                method,
-                r#"      return this.prisma.{}.findMany({{
-        where: data,
-      }})
-  }}"#,
-                lowercase_first_char(model_name)
-            )
-            .unwrap();
-
-            method
-        }
-        RepositoryOperations::Update => {
-            let mut method = format!(
-                "async update(id: string, data: {}): Promise<{}> {{\n",
-                input_type, return_type
-            );
+/// A value object used by the fields of a generated file.
+#[derive(Debug, Serialize)]
+struct ValueObjectImport {
+    name: String,
+    /// Import specifier relative to the generated file.
+    path: String,
+}
 
-            if has_mapper {
-                write!(
-                    method,
-                    r#"    const result = await this.prisma.{}.update({{
-      where: {{
-        id,
-      }},
-      data,
-    }})
-
-    return {}Mapper.toDomain(result)
-  }}"#,
-                    lowercase_first_char(model_name),
-                    model_name
-                )
-                .unwrap();
-
-                return method;
-            }
+/// Everything a template can refer to, documented in the README.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TemplateContext<'a> {
+    schema: Option<&'a Schema>,
+    model: Option<&'a Model>,
+    names: Names,
+    fields: Vec<FieldContext<'a>>,
+    value_objects: Vec<ValueObjectImport>,
+    read_only: bool,
+    repository: Option<RepositoryContext>,
+}
+
+impl<'a> TemplateContext<'a> {
+    fn new(name: &str, fields: &'a [Field], config: &Config) -> Self {
+        TemplateContext {
+            schema: None,
+            model: None,
+            names: Names::new(name, &config.naming),
+            fields: fields
+                .iter()
+                .map(|field| FieldContext::new(field, config))
+                .collect(),
+            value_objects: Vec::new(),
+            read_only: false,
+            repository: None,
+        }
+    }
 
-            write!(
-                method,
-                r#"      return this.prisma.{}.update({{
-        where: {{
-          id,
-        }},
-        data,
-      }})
-  }}"#,
-                lowercase_first_char(model_name)
-            )
-            .unwrap();
-
-            method
+    fn for_model(model: &'a Model, config: &Config) -> Self {
+        TemplateContext {
+            model: Some(model),
+            read_only: model.is_view,
+            ..TemplateContext::new(&model.name, &model.fields, config)
         }
     }
 }
 
+#[derive(Debug, Serialize)]
+struct SchemaModelContext<'a> {
+    model: &'a Model,
+    names: Names,
+}
+
+/// Context of schema-scoped custom artifacts, which see every model at once.
+#[derive(Debug, Serialize)]
+struct SchemaTemplateContext<'a> {
+    schema: &'a Schema,
+    models: Vec<SchemaModelContext<'a>>,
+}
+
+fn create_custom_artifact(
+    artifact: &CustomArtifact,
+    model: &Model,
+    schema: &Schema,
+    config: &Config,
+    templates: &Templates,
+) -> Result<String> {
+    match artifact.scope {
+        ArtifactScope::Model => templates.render(
+            &artifact.template,
+            TemplateContext {
+                schema: Some(schema),
+                ..TemplateContext::for_model(model, config)
+            },
+        ),
+        ArtifactScope::Schema => templates.render(
+            &artifact.template,
+            SchemaTemplateContext {
+                schema,
+                models: schema
+                    .entities()
+                    .map(|model| SchemaModelContext {
+                        model,
+                        names: Names::new(&model.name, &config.naming),
+                    })
+                    .collect(),
+            },
+        ),
+    }
+}
+
 fn create_repository(
     model: &Model,
     methods: Option<Vec<RepositoryOperations>>,
     has_mapper: bool,
     has_entity: bool,
-) -> (String, String) {
-    let mut abstract_repository = format!("export abstract class {}Repository {{", model.name);
-    let mut prisma_repository = format!(
-        r#"@Injectable()
-export class Prisma{}Repository implements {}Repository {{
-    constructor(private readonly prisma: PrismaService) {{}}"#,
-        model.name, model.name
-    );
-
+    config: &Config,
+    templates: &Templates,
+) -> Result<(String, String)> {
     let (input_type, return_type) = if has_entity {
         (format!("Partial<{}>", model.name), model.name.clone())
     } else {
         ("any".to_string(), "any".to_string())
     };
 
-    let methods = methods.unwrap_or_default();
-
-    for method in &methods {
-        match method {
-            RepositoryOperations::Create => {
-                let create_input_type = if has_entity { &model.name } else { &input_type };
-                write!(
-                    abstract_repository,
-                    "\n\t\tabstract create(data: {}): Promise<{}>",
-                    create_input_type, return_type
-                )
-                .unwrap()
-            }
-            RepositoryOperations::Find => write!(
-                abstract_repository,
-                "\n\t\tabstract find(data: {}): Promise<{}>",
-                input_type, return_type
-            )
-            .unwrap(),
-            RepositoryOperations::FindMany => write!(
-                abstract_repository,
-                "\n\t\tabstract findMany(data: {}): Promise<{}[]>",
-                input_type, return_type
-            )
-            .unwrap(),
-            RepositoryOperations::Update => write!(
-                abstract_repository,
-                "\n\t\tabstract update(id: string, data: {}): Promise<{}>",
-                input_type, return_type
-            )
-            .unwrap(),
-            RepositoryOperations::Delete => write!(
-                abstract_repository,
-                "\n\t\tabstract delete(id: string): Promise<void>"
-            )
-            .unwrap(),
-        }
-
-        write!(
-            prisma_repository,
-            "\n\t\t{}",
-            build_repository_methods(&model.name, &input_type, &return_type, has_mapper, method)
-        )
-        .unwrap();
-    }
-
-    write!(abstract_repository, "\n}}").unwrap();
-    write!(prisma_repository, "\n}}").unwrap();
+    let operations = methods
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|method| !model.is_view || method.is_read_only())
+        .collect();
+
+    let context = TemplateContext {
+        repository: Some(RepositoryContext {
+            operations,
+            create_input_type: if has_entity {
+                model.name.clone()
+            } else {
+                input_type.clone()
+            },
+            input_type,
+            return_type,
+            has_mapper,
+            has_entity,
+        }),
+        ..TemplateContext::for_model(model, config)
+    };
 
-    (abstract_repository, prisma_repository)
+    Ok((
+        templates.render(REPOSITORY_TEMPLATE, &context)?,
+        templates.render(PRISMA_REPOSITORY_TEMPLATE, &context)?,
+    ))
 }
 
-fn create_mapper(model: &Model) -> String {
-    let mut mapper = String::new();
-    write!(
-        mapper,
-        "export class {}Mapper {{\n\tstatic toDomain(data: Prisma{}): {} {{\n\t\treturn new {}({{",
-        model.name, model.name, model.name, model.name
+fn create_mapper(
+    model: &Model,
+    value_objects: Vec<ValueObjectImport>,
+    config: &Config,
+    templates: &Templates,
+) -> Result<String> {
+    templates.render(
+        MAPPER_TEMPLATE,
+        TemplateContext {
+            value_objects,
+            ..TemplateContext::for_model(model, config)
+        },
     )
-    .unwrap();
-
-    for field in &model.fields {
-        if get_field_with_type(field, false).is_some() {
-            match field.field_type.as_str() {
-                "Decimal" | "BigInt" => write!(
-                    mapper,
-                    "\n\t\t\t{}: Number(data.{}),",
-                    field.name, field.name
-                )
-                .unwrap(),
-                _ => write!(mapper, "\n\t\t\t{}: data.{},", field.name, field.name).unwrap(),
-            }
-        }
-    }
-
-    write!(mapper, "\n\t\t}})\n\t}}\n}}").unwrap();
-
-    mapper
 }
 
-fn create_entity(model: &Model) -> String {
-    let entity_interface = String::from("I") + &model.name;
-    let mut entity = String::new();
-
-    write!(entity, "export interface {} {{", entity_interface).unwrap();
-
-    for field in &model.fields {
-        let parsed_field_option = get_field_with_type(field, false);
-
-        if let Some(parsed_field) = parsed_field_option {
-            entity.push_str(&parsed_field);
-        }
-    }
-
-    entity.push_str("\n}\n\n");
+fn create_value_object(
+    composite: &CompositeType,
+    value_objects: Vec<ValueObjectImport>,
+    config: &Config,
+    templates: &Templates,
+) -> Result<String> {
+    templates.render(
+        VALUE_OBJECT_TEMPLATE,
+        TemplateContext {
+            value_objects,
+            ..TemplateContext::new(&composite.name, &composite.fields, config)
+        },
+    )
+}
 
-    write!(
-        entity,
-        "export class {} implements {} {{",
-        model.name, entity_interface
+fn create_entity(
+    model: &Model,
+    value_objects: Vec<ValueObjectImport>,
+    config: &Config,
+    templates: &Templates,
+) -> Result<String> {
+    templates.render(
+        ENTITY_TEMPLATE,
+        TemplateContext {
+            value_objects,
+            ..TemplateContext::for_model(model, config)
+        },
     )
-    .unwrap();
+}
 
-    for field in &model.fields {
-        let parsed_field_option = get_field_with_type(field, true);
-        if let Some(parsed_field) = parsed_field_option {
-            entity.push_str(&parsed_field);
+/// Collects the composite types embedded in `fields`, including the ones
+/// nested inside other composite types.
+fn collect_value_objects<'a>(
+    schema: &'a Schema,
+    fields: &[Field],
+    found: &mut Vec<&'a CompositeType>,
+) {
+    for field in fields.iter().filter(|f| f.kind == FieldKind::Composite) {
+        if let Some(composite) = schema.composite_type(&field.field_type) {
+            if !found.iter().any(|known| known.name == composite.name) {
+                found.push(composite);
+                collect_value_objects(schema, &composite.fields, found);
+            }
         }
     }
-
-    let param_name = lowercase_first_char(&model.name);
-
-    writeln!(
-        entity,
-        "\n\n\tconstructor({}: {}) {{\n\t\tObject.assign(this, {})\n\t}}\n}}",
-        param_name, entity_interface, param_name,
-    )
-    .unwrap();
-
-    entity
 }
 
-fn build_type_string(
-    field_type: &str,
-    field_name: &str,
-    is_optional: bool,
-    read_only: bool,
-) -> String {
-    let mut formatted_field_type = String::new();
-    if read_only {
-        write!(
-            formatted_field_type,
-            "\n\treadonly {}: {}",
-            field_name, field_type
-        )
-        .unwrap();
-    } else {
-        write!(formatted_field_type, "\n\t{}: {}", field_name, field_type).unwrap();
-    };
-
-    if is_optional {
-        write!(formatted_field_type, " | null").unwrap();
+/// Returns the TypeScript type of a field's scalar or composite type, without
+/// list or nullability markers.
+fn resolve_ts_type<'a>(
+    field: &'a Field,
+    type_mappings: &'a HashMap<String, String>,
+) -> Option<&'a str> {
+    if let Some(mapped) = type_mappings.get(&field.field_type) {
+        return Some(mapped);
     }
 
-    formatted_field_type
-}
-
-fn get_field_with_type(field: &Field, read_only: bool) -> Option<String> {
-    match field.field_type.as_str() {
-        "Float" | "Int" | "Decimal" | "BigInt" => Some(build_type_string(
-            "number",
-            &field.name,
-            field.is_optional,
-            read_only,
-        )),
-        "String" => Some(build_type_string(
-            "string",
-            &field.name,
-            field.is_optional,
-            read_only,
-        )),
-        "Boolean" => Some(build_type_string(
-            "boolean",
-            &field.name,
-            field.is_optional,
-            read_only,
-        )),
-        "DateTime" => Some(build_type_string(
-            "Date",
-            &field.name,
-            field.is_optional,
-            read_only,
-        )),
-        _ => None,
-    }
+    let ts_type = match field.field_type.as_str() {
+        composite if field.kind == FieldKind::Composite => composite,
+        "Float" | "Int" | "Decimal" | "BigInt" => "number",
+        "String" => "string",
+        "Boolean" => "boolean",
+        "DateTime" => "Date",
+        _ => return None,
+    };
+
+    Some(ts_type)
 }
 
 fn to_kebab_case(name: &str) -> String {
@@ -453,50 +424,180 @@ fn to_kebab_case(name: &str) -> String {
     kebab_case_string
 }
 
-fn build_path(dir: &Path, module_path: &str, module_type: ModuleType, model_name: &str) -> String {
-    let kebab_model_name = to_kebab_case(model_name);
-    let (path, file_name) = match module_type {
-        ModuleType::Entity => (ENTITY_PATH, format!("{}.entity.ts", kebab_model_name)),
-        ModuleType::Mapper => (MAPPER_PATH, format!("{}.mapper.ts", kebab_model_name)),
-        ModuleType::Repository(_) => (
-            REPOSITORY_PATH,
-            format!("{}.repository.ts", kebab_model_name),
-        ),
-        ModuleType::PrismaRepository => (
-            PRISMA_REPOSITORY_PATH,
-            format!("prisma-{}.repository.ts", kebab_model_name),
-        ),
-    };
+/// Import specifier of `file` from a module in `dir`, without extension.
+pub fn import_path(dir: &Path, file: &Path) -> String {
+    let file = file.with_extension("");
+    let dir: Vec<Component> = dir.components().collect();
+    let target: Vec<Component> = file.components().collect();
+    let common = dir.iter().zip(&target).take_while(|(a, b)| a == b).count();
 
-    format!("{}/{}{}/{}", dir.display(), module_path, path, file_name)
+    let mut path = PathBuf::new();
+    for _ in common..dir.len() {
+        path.push("..");
+    }
+    for component in &target[common..] {
+        path.push(component);
+    }
+
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with("..") {
+        path
+    } else {
+        format!("./{}", path)
+    }
+}
+
+fn render_pattern(pattern: &str, model_name: &str) -> String {
+    pattern
+        .replace("{name}", model_name)
+        .replace("{camel}", &lowercase_first_char(model_name))
+        .replace("{kebab}", &to_kebab_case(model_name))
 }
 
-fn write_to_module<P: AsRef<Path>>(path: P, contents: String) -> std::io::Result<()> {
-    if let Some(parent) = path.as_ref().parent() {
-        fs::create_dir_all(parent)?;
+/// Directory the artifact paths of a tsconfig module are relative to: the
+/// module itself, or the project root when `nestUnderModule` is off.
+pub fn module_root(dir: &Path, module_path: &str, config: &Config) -> PathBuf {
+    if config.output.nest_under_module {
+        dir.join(module_path)
+    } else {
+        dir.to_path_buf()
     }
+}
 
-    let mut file = fs::File::create(path)?;
-    file.write_all(contents.as_bytes())?;
+fn build_path(
+    root: &Path,
+    config: &Config,
+    module_type: ModuleType,
+    model_name: &str,
+) -> Result<PathBuf> {
+    let (path, file_name) = config.layout(&module_type)?;
 
-    Ok(())
+    Ok(root
+        .join(render_pattern(path, model_name))
+        .join(render_pattern(file_name, model_name)))
 }
 
-pub fn write_modules(modules: Vec<ModuleType>, dir: &Path, module_path: &str, model: &Model) {
+/// Resolves the modules of a configured target, filling in the repository
+/// operations.
+pub fn target_modules(target: &GenerateTarget, config: &Config) -> Result<Vec<ModuleType>> {
+    target
+        .artifacts
+        .as_ref()
+        .unwrap_or(&config.artifacts)
+        .iter()
+        .map(|name| {
+            match ModuleType::from_name(name, config)
+                .ok_or_else(|| Error::UnknownArtifact(name.clone()))?
+            {
+                ModuleType::Repository(_) => Ok(ModuleType::Repository(Some(
+                    target
+                        .repository_operations
+                        .clone()
+                        .unwrap_or_else(|| config.repository_operations.clone()),
+                ))),
+                module => Ok(module),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    /// The model, view or composite type the file was generated from, unset
+    /// for schema-wide artifacts.
+    pub model: Option<String>,
+    pub artifact: ModuleType,
+    pub contents: String,
+}
+
+/// Renders every selected module in memory, without touching the file system.
+/// Artifact paths are relative to `root`.
+pub fn generate_modules(
+    modules: Vec<ModuleType>,
+    root: &Path,
+    model: &Model,
+    schema: &Schema,
+    config: &Config,
+    templates: &Templates,
+) -> Result<Vec<GeneratedFile>> {
+    let mut files = Vec::new();
+    let path = |artifact: ModuleType, name: &str| build_path(root, config, artifact, name);
+    let mut write = |artifact: ModuleType, source: Option<&str>, contents: String| {
+        let name = source.unwrap_or(&model.name);
+        files.push(GeneratedFile {
+            path: path(artifact.clone(), name)?,
+            model: source.map(str::to_string),
+            artifact,
+            contents: config.formatting.apply(contents),
+        });
+
+        Ok::<(), Error>(())
+    };
+    // The value objects referenced by `fields`, imported from the file that
+    // `artifact` of `name` is written to.
+    let imports = |fields: &[Field], artifact: ModuleType, name: &str| {
+        let from = path(artifact, name)?;
+        let from = from.parent().unwrap_or(root);
+        let mut value_objects: Vec<ValueObjectImport> = Vec::new();
+
+        for field in fields.iter().filter(|f| f.kind == FieldKind::Composite) {
+            if !value_objects
+                .iter()
+                .any(|known| known.name == field.field_type)
+            {
+                value_objects.push(ValueObjectImport {
+                    name: field.field_type.clone(),
+                    path: import_path(from, &path(ModuleType::ValueObject, &field.field_type)?),
+                });
+            }
+        }
+
+        Ok::<_, Error>(value_objects)
+    };
+
     for module in &modules {
         match module {
-            ModuleType::Entity => write_to_module(
-                build_path(dir, module_path, ModuleType::Entity, &model.name),
-                create_entity(model),
-            )
-            .unwrap(),
+            ModuleType::Entity => {
+                write(
+                    ModuleType::Entity,
+                    Some(&model.name),
+                    create_entity(
+                        model,
+                        imports(&model.fields, ModuleType::Entity, &model.name)?,
+                        config,
+                        templates,
+                    )?,
+                )?;
+
+                let mut value_objects = Vec::new();
+                collect_value_objects(schema, &model.fields, &mut value_objects);
+
+                for composite in value_objects {
+                    write(
+                        ModuleType::ValueObject,
+                        Some(&composite.name),
+                        create_value_object(
+                            composite,
+                            imports(&composite.fields, ModuleType::ValueObject, &composite.name)?,
+                            config,
+                            templates,
+                        )?,
+                    )?;
+                }
+            }
             ModuleType::Mapper => {
                 if modules.contains(&ModuleType::Entity) {
-                    write_to_module(
-                        build_path(dir, module_path, ModuleType::Mapper, &model.name),
-                        create_mapper(model),
-                    )
-                    .unwrap()
+                    write(
+                        ModuleType::Mapper,
+                        Some(&model.name),
+                        create_mapper(
+                            model,
+                            imports(&model.fields, ModuleType::Mapper, &model.name)?,
+                            config,
+                            templates,
+                        )?,
+                    )?
                 }
             }
             ModuleType::Repository(methods) => {
@@ -507,21 +608,161 @@ pub fn write_modules(modules: Vec<ModuleType>, dir: &Path, module_path: &str, mo
                     methods.clone(),
                     modules.contains(&ModuleType::Mapper) && has_entity,
                     has_entity,
-                );
+                    config,
+                    templates,
+                )?;
 
-                write_to_module(
-                    build_path(dir, module_path, ModuleType::Repository(None), &model.name),
+                write(
+                    ModuleType::Repository(None),
+                    Some(&model.name),
                     abstract_repository,
-                )
-                .unwrap();
+                )?;
 
-                write_to_module(
-                    build_path(dir, module_path, ModuleType::PrismaRepository, &model.name),
+                write(
+                    ModuleType::PrismaRepository,
+                    Some(&model.name),
                     prisma_repository,
-                )
-                .unwrap();
+                )?;
+            }
+            ModuleType::Custom(name) => {
+                let artifact = config
+                    .custom_artifact(name)
+                    .ok_or_else(|| Error::UnknownArtifact(name.clone()))?;
+
+                let source = match artifact.scope {
+                    ArtifactScope::Model => Some(model.name.as_str()),
+                    ArtifactScope::Schema => None,
+                };
+
+                write(
+                    module.clone(),
+                    source,
+                    create_custom_artifact(artifact, model, schema, config, templates)?,
+                )?;
+            }
+            // Generated along with the entity and the repository.
+            ModuleType::PrismaRepository | ModuleType::ValueObject => {}
+        }
+    }
+
+    Ok(files)
+}
+
+/// Renders every target configured under `generate` in memory.
+pub fn generate_targets(
+    dir: &Path,
+    schema: &Schema,
+    ts_config: &TsConfig,
+    config: &Config,
+    templates: &Templates,
+) -> Result<Vec<GeneratedFile>> {
+    let mut files = Vec::new();
+
+    for target in &config.generate {
+        let model = schema
+            .entities()
+            .find(|model| model.name == target.model)
+            .ok_or_else(|| {
+                Error::InvalidConfig(format!("model {} not found in prisma schema", target.model))
+            })?;
+
+        let module = ts_config.module(&target.module).ok_or_else(|| {
+            Error::InvalidConfig(format!(
+                "module {} not found in tsconfig.json",
+                target.module
+            ))
+        })?;
+
+        files.extend(generate_modules(
+            target_modules(target, config)?,
+            &module_root(dir, &module.dirs[0], config),
+            model,
+            schema,
+            config,
+            templates,
+        )?);
+    }
+
+    Ok(files)
+}
+
+/// Writes `files` and records them in the manifest. Returns the files that
+/// now hold their generated contents, or with `dry_run` would hold them, i.e.
+/// not the ones skipped or written as `.new`.
+pub fn write_modules<'a>(
+    files: &'a [GeneratedFile],
+    writer: &Writer,
+    manifest: &mut Manifest,
+) -> Result<Vec<&'a GeneratedFile>> {
+    let mut written = Vec::new();
+
+    for file in files {
+        let modified = manifest.is_modified(&file.path);
+
+        if writer.dry_run {
+            writer.write(&file.path, &file.contents, modified)?;
+            written.push(file);
+        } else if writer.write(&file.path, &file.contents, modified)? {
+            if writer.barrels {
+                add_export(&file.path).map_err(Error::io(&file.path))?;
             }
-            _ => unreachable!(),
+
+            manifest.record(
+                &file.path,
+                file.model.as_deref(),
+                file.artifact.label(),
+                &file.contents,
+            );
+            written.push(file);
         }
     }
+
+    if !writer.dry_run {
+        manifest.save()?;
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_schema;
+
+    #[test]
+    fn imports_value_objects_where_they_are_used() {
+        let schema = parse_schema(
+            "model User {\n  id Int @id\n  address Address?\n}\n\
+             type Address {\n  street String\n  geo Geo\n}\n\
+             type Geo {\n  lat Float\n}\n",
+        )
+        .unwrap();
+        let config = Config::default();
+        let files = generate_modules(
+            vec![ModuleType::Entity, ModuleType::Mapper],
+            Path::new("/project/src/users"),
+            &schema.models[0],
+            &schema,
+            &config,
+            &Templates::new(PathBuf::from("/nonexistent")),
+        )
+        .unwrap();
+
+        let contents = |name: &str| {
+            &files
+                .iter()
+                .find(|file| file.path.ends_with(name))
+                .unwrap()
+                .contents
+        };
+
+        assert!(contents("user.entity.ts")
+            .starts_with("import { Address } from './address.value-object'\n"));
+        assert!(contents("address.value-object.ts")
+            .starts_with("import { Geo } from './geo.value-object'\n"));
+        assert!(contents("geo.value-object.ts").starts_with("// <custom:imports>"));
+        assert!(contents("user.mapper.ts").starts_with(
+            "import { Address } from '../../../../domain/entity/address.value-object'\n"
+        ));
+    }
 }
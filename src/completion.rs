@@ -0,0 +1,48 @@
+use std::{fs, path::PathBuf};
+
+use dialoguer::Completion;
+
+/// Completes the last segment of a directory path typed in a prompt to the
+/// longest prefix shared by the matching subdirectories, relative to `root`.
+pub struct DirectoryCompletion {
+    pub root: PathBuf,
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or(a.len().min(b.len()), |((index, _), _)| index);
+
+    &a[..len]
+}
+
+impl Completion for DirectoryCompletion {
+    fn get(&self, input: &str) -> Option<String> {
+        let (parent, partial) = match input.rsplit_once('/') {
+            Some((parent, partial)) => (format!("{}/", parent), partial),
+            None => (String::new(), input),
+        };
+
+        let mut matches: Vec<String> = fs::read_dir(self.root.join(&parent))
+            .ok()?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name.starts_with(partial) && !name.starts_with('.'))
+            .collect();
+        matches.sort();
+
+        let completed = match matches.as_slice() {
+            [] => return None,
+            [only] => format!("{}/", only),
+            [first, rest @ ..] => rest
+                .iter()
+                .fold(first.as_str(), |prefix, name| common_prefix(prefix, name))
+                .to_string(),
+        };
+
+        Some(format!("{}{}", parent, completed))
+    }
+}
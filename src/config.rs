@@ -0,0 +1,317 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{
+    de::{self, Unexpected},
+    Deserialize, Deserializer,
+};
+
+use crate::code_gen::{ModuleType, RepositoryOperations};
+use crate::error::{Error, Result};
+
+/// Looked up in the project root, first match wins. All of them hold JSON.
+pub const CONFIG_FILES: [&str; 3] = [
+    "entity-generator.config.json",
+    ".entitygenrc",
+    ".entitygenrc.json",
+];
+
+const ENTITY_PATH: &str = "domain/entity";
+const MAPPER_PATH: &str = "infra/database/prisma/mappers";
+const REPOSITORY_PATH: &str = "app/repositories";
+const PRISMA_REPOSITORY_PATH: &str = "infra/database/prisma";
+
+/// Where a single kind of artifact is written. Both values accept the
+/// `{name}`, `{camel}` and `{kebab}` placeholders for the model name.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactOutput {
+    pub path: Option<String>,
+    pub file_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OutputConfig {
+    /// Whether artifact paths are relative to the selected tsconfig module or
+    /// to the project root.
+    pub nest_under_module: bool,
+    pub entity: ArtifactOutput,
+    pub value_object: ArtifactOutput,
+    pub mapper: ArtifactOutput,
+    pub repository: ArtifactOutput,
+    pub prisma_repository: ArtifactOutput,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        OutputConfig {
+            nest_under_module: true,
+            entity: ArtifactOutput::default(),
+            value_object: ArtifactOutput::default(),
+            mapper: ArtifactOutput::default(),
+            repository: ArtifactOutput::default(),
+            prisma_repository: ArtifactOutput::default(),
+        }
+    }
+}
+
+impl OutputConfig {
+    /// Returns the directory and file name patterns for `module_type`.
+    pub fn layout(&self, module_type: &ModuleType) -> Option<(&str, &str)> {
+        let (output, path, file_name) = match module_type {
+            ModuleType::Entity => (&self.entity, ENTITY_PATH, "{kebab}.entity.ts"),
+            ModuleType::ValueObject => (&self.value_object, ENTITY_PATH, "{kebab}.value-object.ts"),
+            ModuleType::Mapper => (&self.mapper, MAPPER_PATH, "{kebab}.mapper.ts"),
+            ModuleType::Repository(_) => {
+                (&self.repository, REPOSITORY_PATH, "{kebab}.repository.ts")
+            }
+            ModuleType::PrismaRepository => (
+                &self.prisma_repository,
+                PRISMA_REPOSITORY_PATH,
+                "prisma-{kebab}.repository.ts",
+            ),
+            ModuleType::Custom(_) => return None,
+        };
+
+        Some((
+            output.path.as_deref().unwrap_or(path),
+            output.file_name.as_deref().unwrap_or(file_name),
+        ))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NamingConfig {
+    pub interface_prefix: String,
+    pub mapper_suffix: String,
+    pub repository_suffix: String,
+    pub prisma_repository_prefix: String,
+}
+
+impl Default for NamingConfig {
+    fn default() -> Self {
+        NamingConfig {
+            interface_prefix: "I".to_string(),
+            mapper_suffix: "Mapper".to_string(),
+            repository_suffix: "Repository".to_string(),
+            prisma_repository_prefix: "Prisma".to_string(),
+        }
+    }
+}
+
+impl NamingConfig {
+    pub fn interface(&self, name: &str) -> String {
+        format!("{}{}", self.interface_prefix, name)
+    }
+
+    pub fn mapper(&self, name: &str) -> String {
+        format!("{}{}", name, self.mapper_suffix)
+    }
+
+    pub fn repository(&self, name: &str) -> String {
+        format!("{}{}", name, self.repository_suffix)
+    }
+
+    pub fn prisma_repository(&self, name: &str) -> String {
+        format!("{}{}", self.prisma_repository_prefix, self.repository(name))
+    }
+}
+
+/// Written as `"tab"` or a number of spaces, other values are rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tab,
+}
+
+#[derive(Deserialize)]
+#[serde(
+    untagged,
+    expecting = "invalid indent, expected a number of spaces or \"tab\""
+)]
+enum RawIndent {
+    Spaces(usize),
+    Keyword(String),
+}
+
+impl<'de> Deserialize<'de> for Indent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match RawIndent::deserialize(deserializer)? {
+            RawIndent::Spaces(width) => Ok(Indent::Spaces(width)),
+            RawIndent::Keyword(keyword) if keyword == "tab" => Ok(Indent::Tab),
+            RawIndent::Keyword(keyword) => Err(de::Error::invalid_value(
+                Unexpected::Str(&keyword),
+                &"a number of spaces or \"tab\"",
+            )),
+        }
+    }
+}
+
+impl Indent {
+    /// One level of indentation.
+    pub fn unit(&self) -> String {
+        match self {
+            Indent::Spaces(width) => " ".repeat(*width),
+            Indent::Tab => "\t".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FormattingConfig {
+    pub indent: Indent,
+    pub final_newline: bool,
+}
+
+impl Default for FormattingConfig {
+    fn default() -> Self {
+        FormattingConfig {
+            indent: Indent::Tab,
+            final_newline: false,
+        }
+    }
+}
+
+impl FormattingConfig {
+    /// Generators indent with tabs, this swaps them for the configured
+    /// indentation.
+    pub fn apply(&self, contents: String) -> String {
+        let mut formatted = match self.indent {
+            Indent::Tab => contents,
+            Indent::Spaces(width) => contents
+                .split('\n')
+                .map(|line| {
+                    let body = line.trim_start_matches('\t');
+                    let tabs = line.len() - body.len();
+                    " ".repeat(tabs * width) + body
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        if self.final_newline && !formatted.ends_with('\n') {
+            formatted.push('\n');
+        }
+
+        formatted
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArtifactScope {
+    /// Rendered once for the selected model.
+    #[default]
+    Model,
+    /// Rendered once with every model of the schema.
+    Schema,
+}
+
+/// An artifact kind declared by the project, offered next to the built-in
+/// ones and rendered from its own template.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomArtifact {
+    pub name: String,
+    pub template: String,
+    pub path: String,
+    pub file_name: String,
+    #[serde(default)]
+    pub scope: ArtifactScope,
+}
+
+/// A model whose artifacts are regenerated without prompts, e.g. by `check`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateTarget {
+    pub model: String,
+    /// Output module, as listed in the "Select output module" prompt.
+    pub module: String,
+    /// Defaults to the top-level `artifacts`.
+    pub artifacts: Option<Vec<String>>,
+    /// Defaults to the top-level `repositoryOperations`.
+    pub repository_operations: Option<Vec<RepositoryOperations>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Config {
+    /// Artifacts checked by default in the "Select which classes to create" prompt.
+    pub artifacts: Vec<String>,
+    pub repository_operations: Vec<RepositoryOperations>,
+    pub output: OutputConfig,
+    pub naming: NamingConfig,
+    /// Overrides the TypeScript type generated for a Prisma scalar, e.g.
+    /// `"Decimal": "Prisma.Decimal"`.
+    pub type_mappings: HashMap<String, String>,
+    pub formatting: FormattingConfig,
+    /// Directory, relative to the project root, whose templates override the
+    /// built-in ones.
+    pub templates_dir: String,
+    pub custom_artifacts: Vec<CustomArtifact>,
+    pub generate: Vec<GenerateTarget>,
+    /// Keep an `index.ts` re-exporting every generated file in its directory.
+    pub barrels: bool,
+    /// NestJS module, relative to the project root, that generated
+    /// repositories are registered in. Created when missing.
+    pub database_module: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            artifacts: vec!["entity".to_string()],
+            repository_operations: vec![RepositoryOperations::Find],
+            output: OutputConfig::default(),
+            naming: NamingConfig::default(),
+            type_mappings: HashMap::new(),
+            formatting: FormattingConfig::default(),
+            templates_dir: ".entity-generator/templates".to_string(),
+            custom_artifacts: Vec::new(),
+            generate: Vec::new(),
+            barrels: true,
+            database_module: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn custom_artifact(&self, name: &str) -> Option<&CustomArtifact> {
+        self.custom_artifacts
+            .iter()
+            .find(|artifact| artifact.name == name)
+    }
+
+    /// Returns the directory and file name patterns for built-in and custom
+    /// artifacts alike.
+    pub fn layout(&self, module_type: &ModuleType) -> Result<(&str, &str)> {
+        match module_type {
+            ModuleType::Custom(name) => self
+                .custom_artifact(name)
+                .map(|artifact| (artifact.path.as_str(), artifact.file_name.as_str())),
+            _ => self.output.layout(module_type),
+        }
+        .ok_or_else(|| Error::UnknownArtifact(module_type.label().to_string()))
+    }
+
+    pub fn load(root: &Path) -> Result<Self> {
+        let Some((path, content)) = CONFIG_FILES.iter().find_map(|file| {
+            let path = root.join(file);
+            fs::read_to_string(&path)
+                .ok()
+                .map(|content| (path, content))
+        }) else {
+            return Ok(Config::default());
+        };
+
+        serde_json::from_str(&content).map_err(|err| {
+            Error::InvalidConfig(format!(
+                "invalid config at path {}: {}",
+                path.display(),
+                err
+            ))
+        })
+    }
+}
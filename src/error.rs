@@ -0,0 +1,100 @@
+use std::{fmt, io, path::PathBuf};
+
+use crate::lexer::ParseError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// An invalid command line argument.
+    Usage(String),
+    SchemaNotFound(PathBuf),
+    Parse {
+        path: PathBuf,
+        error: ParseError,
+    },
+    TsConfigInvalid {
+        path: PathBuf,
+        message: String,
+    },
+    /// An invalid project config, manifest, or a reference in them that does
+    /// not resolve.
+    InvalidConfig(String),
+    UnknownArtifact(String),
+    Template {
+        name: String,
+        message: String,
+    },
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// Reading from or writing to the terminal failed.
+    Terminal(io::Error),
+    Watch(notify::Error),
+    /// `check` found generated files that differ from the schema.
+    OutOfDate,
+}
+
+impl Error {
+    pub fn io(path: impl Into<PathBuf>) -> impl FnOnce(io::Error) -> Error {
+        let path = path.into();
+        move |error| Error::Io { path, error }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::OutOfDate => 1,
+            Error::Usage(_) => 2,
+            Error::SchemaNotFound(_) => 3,
+            Error::Parse { .. } => 4,
+            Error::TsConfigInvalid { .. } => 5,
+            Error::InvalidConfig(_) | Error::UnknownArtifact(_) | Error::Template { .. } => 6,
+            Error::Io { .. } | Error::Terminal(_) | Error::Watch(_) => 7,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(message) => write!(f, "{}", message),
+            Error::SchemaNotFound(path) => {
+                write!(f, "no prisma schema found at path {}", path.display())
+            }
+            Error::Parse { path, error } => write!(f, "{}:{}", path.display(), error),
+            Error::TsConfigInvalid { path, message } => {
+                write!(
+                    f,
+                    "invalid tsconfig at path {}: {}",
+                    path.display(),
+                    message
+                )
+            }
+            Error::InvalidConfig(message) => write!(f, "{}", message),
+            Error::UnknownArtifact(name) => write!(f, "unknown artifact {}", name),
+            Error::Template { name, message } => {
+                write!(f, "failed to render template {}: {}", name, message)
+            }
+            Error::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            Error::Terminal(error) => write!(f, "terminal error: {}", error),
+            Error::Watch(error) => write!(f, "failed to watch schema: {}", error),
+            Error::OutOfDate => write!(f, "generated files are out of date"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<dialoguer::Error> for Error {
+    fn from(error: dialoguer::Error) -> Self {
+        let dialoguer::Error::IO(error) = error;
+        Error::Terminal(error)
+    }
+}
+
+impl From<notify::Error> for Error {
+    fn from(error: notify::Error) -> Self {
+        Error::Watch(error)
+    }
+}
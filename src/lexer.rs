@@ -0,0 +1,239 @@
+use core::fmt;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    String(String),
+    Number(String),
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Colon,
+    Equals,
+    Question,
+    Dot,
+    At,
+    AtAt,
+    Newline,
+    Eof,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Ident(ident) => write!(f, "`{}`", ident),
+            TokenKind::String(value) => write!(f, "string \"{}\"", value),
+            TokenKind::Number(value) => write!(f, "number {}", value),
+            TokenKind::LBrace => write!(f, "`{{`"),
+            TokenKind::RBrace => write!(f, "`}}`"),
+            TokenKind::LParen => write!(f, "`(`"),
+            TokenKind::RParen => write!(f, "`)`"),
+            TokenKind::LBracket => write!(f, "`[`"),
+            TokenKind::RBracket => write!(f, "`]`"),
+            TokenKind::Comma => write!(f, "`,`"),
+            TokenKind::Colon => write!(f, "`:`"),
+            TokenKind::Equals => write!(f, "`=`"),
+            TokenKind::Question => write!(f, "`?`"),
+            TokenKind::Dot => write!(f, "`.`"),
+            TokenKind::At => write!(f, "`@`"),
+            TokenKind::AtAt => write!(f, "`@@`"),
+            TokenKind::Newline => write!(f, "end of line"),
+            TokenKind::Eof => write!(f, "end of file"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        ParseError {
+            message: message.into(),
+            line: span.line,
+            column: span.column,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Lexer {
+            source,
+            chars: source.char_indices().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map(|(offset, _)| *offset)
+            .unwrap_or(self.source.len())
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, ch)| *ch)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let (_, ch) = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(ch)
+    }
+
+    fn bump_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&predicate) {
+            self.bump();
+        }
+    }
+
+    fn string(&mut self, start: Span) -> Result<TokenKind, ParseError> {
+        let mut value = String::new();
+
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(TokenKind::String(value)),
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(escaped) => value.push(escaped),
+                    None => break,
+                },
+                Some('\n') | None => break,
+                Some(ch) => value.push(ch),
+            }
+        }
+
+        Err(ParseError::new("unterminated string literal", start))
+    }
+
+    fn next_token(&mut self) -> Result<Token, ParseError> {
+        loop {
+            self.bump_while(|ch| ch.is_whitespace() && ch != '\n');
+
+            let rest = &self.source[self.offset()..];
+            if rest.starts_with("//") {
+                self.bump_while(|ch| ch != '\n');
+            } else {
+                break;
+            }
+        }
+
+        let start = self.offset();
+        let mut span = Span {
+            start,
+            end: start,
+            line: self.line,
+            column: self.column,
+        };
+
+        let Some(ch) = self.bump() else {
+            return Ok(Token {
+                kind: TokenKind::Eof,
+                span,
+            });
+        };
+
+        let kind = match ch {
+            '\n' => TokenKind::Newline,
+            '{' => TokenKind::LBrace,
+            '}' => TokenKind::RBrace,
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            '[' => TokenKind::LBracket,
+            ']' => TokenKind::RBracket,
+            ',' => TokenKind::Comma,
+            ':' => TokenKind::Colon,
+            '=' => TokenKind::Equals,
+            '?' => TokenKind::Question,
+            '.' => TokenKind::Dot,
+            '@' => {
+                if self.peek() == Some('@') {
+                    self.bump();
+                    TokenKind::AtAt
+                } else {
+                    TokenKind::At
+                }
+            }
+            '"' => self.string(span)?,
+            ch if ch.is_ascii_digit() || ch == '-' => {
+                self.bump_while(|ch| ch.is_ascii_digit() || ch == '.');
+                TokenKind::Number(self.source[start..self.offset()].to_string())
+            }
+            ch if ch.is_alphabetic() || ch == '_' => {
+                self.bump_while(|ch| ch.is_alphanumeric() || ch == '_');
+                TokenKind::Ident(self.source[start..self.offset()].to_string())
+            }
+            ch => {
+                return Err(ParseError::new(
+                    format!("unexpected character `{}`", ch),
+                    span,
+                ))
+            }
+        };
+
+        span.end = self.offset();
+
+        Ok(Token { kind, span })
+    }
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<Token>, ParseError> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+
+    loop {
+        let token = lexer.next_token()?;
+        let is_eof = token.kind == TokenKind::Eof;
+        tokens.push(token);
+
+        if is_eof {
+            return Ok(tokens);
+        }
+    }
+}
@@ -1,132 +1,363 @@
-use code_gen::{write_modules, ModuleType, RepositoryOperations};
-use dialoguer::{theme::ColorfulTheme, FuzzySelect, MultiSelect};
-use parser::{get_schemas, parse_schema, TsConfig};
+use check::check;
+use cli::{Args, Command};
+use code_gen::{generate_modules, module_root, write_modules, ModuleType, RepositoryOperations};
+use completion::DirectoryCompletion;
+use config::Config;
+use dialoguer::{theme::ColorfulTheme, FuzzySelect, Input, MultiSelect};
+use error::{Error, Result};
+use manifest::Manifest;
+use nest::register_repositories;
+use parser::{load_schema, parse_schema_files, schema_sources, Model, Schema};
+use project::{find_project_root, resolve_schema_path, Workspace};
+use prune::prune;
 use std::{
     env,
-    fs::{self, File},
-    io::BufReader,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
 };
+use templates::Templates;
+use tsconfig::{Module, TsConfig};
+use watch::watch;
+use writer::Writer;
 
+mod barrel;
+mod check;
+mod cli;
 mod code_gen;
+mod completion;
+mod config;
+mod error;
+mod lexer;
+mod manifest;
+mod nest;
 mod parser;
+mod project;
+mod prune;
+mod regions;
+#[cfg(test)]
+mod temp_dir;
+mod templates;
+mod tsconfig;
+mod watch;
+mod writer;
 
-fn main() {
-    let dir = env::current_dir().unwrap();
+fn write_schema(out: &mut impl Write, schema: &Schema, json: bool) -> io::Result<()> {
+    if json {
+        serde_json::to_writer_pretty(&mut *out, schema)?;
+        return writeln!(out);
+    }
+
+    for model in &schema.models {
+        writeln!(out, "model {} ({} fields)", model.name, model.fields.len())?;
+    }
+
+    for view in &schema.views {
+        writeln!(out, "view {} ({} fields)", view.name, view.fields.len())?;
+    }
+
+    for composite in &schema.types {
+        writeln!(
+            out,
+            "type {} ({} fields)",
+            composite.name,
+            composite.fields.len()
+        )?;
+    }
+
+    for en in &schema.enums {
+        writeln!(out, "enum {} ({} values)", en.name, en.values.len())?;
+    }
+
+    Ok(())
+}
+
+/// Prints the schema to stdout. A reader closing the pipe early, as `head`
+/// does, is not an error.
+fn print_schema(schema: &Schema, json: bool) -> Result<()> {
+    match write_schema(&mut io::stdout().lock(), schema, json) {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result.map_err(Error::Terminal),
+    }
+}
+
+/// Asks for one of the workspace `packages`, preselecting the one the command
+/// runs in. Nothing is asked when there is at most one package.
+fn select_package(
+    prompt: &str,
+    packages: &[&Path],
+    workspace: &Workspace,
+    cwd: &Path,
+) -> Result<Option<PathBuf>> {
+    let selection = match packages {
+        [] => return Ok(None),
+        [_] => 0,
+        _ => {
+            let current = packages
+                .iter()
+                .enumerate()
+                .filter(|(_, package)| cwd.starts_with(package))
+                .max_by_key(|(_, package)| package.components().count())
+                .map_or(0, |(index, _)| index);
+
+            let labels: Vec<String> = packages
+                .iter()
+                .map(|package| workspace.label(package))
+                .collect();
+
+            FuzzySelect::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .default(current)
+                .items(&labels)
+                .interact()?
+        }
+    };
+
+    Ok(Some(packages[selection].to_path_buf()))
+}
 
-    let schemas = get_schemas(format!("{}/prisma", dir.display()))
-        .unwrap_or_else(|_| panic!("prisma schema not found at path {}/prisma", dir.display()));
+const CUSTOM_DIRECTORY: &str = "Custom directory…";
 
-    let schema_file_names: Vec<String> = schemas
+/// Asks for the directory generated files are written under. A custom
+/// directory is used as is, a tsconfig module as its `nestUnderModule` says.
+fn select_output_root(dir: &Path, ts_config: &TsConfig, config: &Config) -> Result<PathBuf> {
+    let modules = ts_config.modules();
+    let module_labels: Vec<String> = modules
         .iter()
-        .filter_map(|schema| {
-            schema
-                .file_name()
-                .map(|name| name.to_string_lossy().to_string())
-        })
+        .map(Module::label)
+        .chain([CUSTOM_DIRECTORY.to_string()])
         .collect();
 
-    let schema_selection = FuzzySelect::with_theme(&ColorfulTheme::default())
-        .with_prompt("Select prisma schema")
-        .default(0)
-        .items(&schema_file_names)
-        .interact()
-        .unwrap();
+    let module_selection = if modules.is_empty() {
+        0
+    } else {
+        FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select output module")
+            .default(0)
+            .items(&module_labels)
+            .interact()?
+    };
 
-    let schema_file = File::open(schemas.get(schema_selection).unwrap()).unwrap();
+    let Some(selected_module) = modules.get(module_selection) else {
+        let completion = DirectoryCompletion {
+            root: dir.to_path_buf(),
+        };
 
-    let reader = BufReader::new(schema_file);
+        let path: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Output directory (Tab completes)")
+            .completion_with(&completion)
+            .interact_text()?;
 
-    let models = parse_schema(reader);
+        return Ok(dir.join(path.trim().trim_end_matches('/')));
+    };
 
-    let model_names: Vec<&str> = models.iter().map(|model| model.name.as_str()).collect();
+    if selected_module.dirs.len() == 1 {
+        return Ok(module_root(dir, &selected_module.dirs[0], config));
+    }
 
-    let model_selection = FuzzySelect::with_theme(&ColorfulTheme::default())
-        .with_prompt("Select model")
+    let dir_selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Select target of {}", selected_module.alias))
         .default(0)
-        .items(&model_names)
-        .interact()
-        .unwrap();
+        .items(&selected_module.dirs)
+        .interact()?;
+
+    Ok(module_root(
+        dir,
+        &selected_module.dirs[dir_selection],
+        config,
+    ))
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        process::exit(err.exit_code());
+    }
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse()?;
+    let cwd = env::current_dir().map_err(Error::io("."))?;
+    let mut dir = find_project_root(&cwd);
+    let cli_schema = args.schema.map(|schema| cwd.join(schema));
+    let mut schema_root = dir.clone();
+
+    if args.command == Command::Generate {
+        if let Some(workspace) = Workspace::find(&cwd) {
+            if cli_schema.is_none() {
+                let packages = workspace.schema_packages();
+                if let Some(package) =
+                    select_package("Select schema package", &packages, &workspace, &cwd)?
+                {
+                    schema_root = package;
+                }
+            }
 
-    let selected_model = models.get(model_selection).unwrap();
+            let packages = workspace.tsconfig_packages();
+            if let Some(package) =
+                select_package("Select target package", &packages, &workspace, &cwd)?
+            {
+                dir = package;
+            }
+        }
+    }
 
-    let ts_config_content = fs::read_to_string(format!("{}/tsconfig.json", dir.display())).unwrap();
+    let config = Config::load(&dir)?;
+    let schema_path = resolve_schema_path(&schema_root, cli_schema.as_deref());
 
-    let ts_config: TsConfig =
-        serde_json::from_str(&ts_config_content).unwrap_or_else(|_| TsConfig::default());
+    if args.command != Command::Generate {
+        let schema = load_schema(&schema_path)?;
 
-    let modules: Vec<String> = ts_config
-        .compiler_options
-        .paths
-        .keys()
-        .map(|key| key.replace('@', "").replace("/*", ""))
+        return match args.command {
+            Command::Schema => print_schema(&schema, args.json),
+            Command::Prune => prune(
+                &dir,
+                &schema,
+                &mut Manifest::load(&dir)?,
+                &config,
+                args.force,
+                args.dry_run,
+            ),
+            Command::Watch => watch(
+                &dir,
+                &schema_path,
+                &TsConfig::load(&dir)?,
+                &config,
+                args.dry_run,
+            ),
+            _ => check(&dir, &schema, &TsConfig::load(&dir)?, &config),
+        };
+    }
+
+    let sources = schema_sources(&schema_path)
+        .ok()
+        .filter(|sources| !sources.is_empty())
+        .ok_or_else(|| Error::SchemaNotFound(schema_path.clone()))?;
+
+    let schema_selection = if sources.len() == 1 {
+        0
+    } else {
+        let schema_file_names: Vec<String> = sources
+            .iter()
+            .map(|files| {
+                files[0]
+                    .strip_prefix(&schema_path)
+                    .unwrap_or(&files[0])
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+
+        FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select prisma schema")
+            .default(0)
+            .items(&schema_file_names)
+            .interact()?
+    };
+
+    let schema = parse_schema_files(&sources[schema_selection])?;
+    let models: Vec<&Model> = schema.entities().collect();
+
+    let model_names: Vec<String> = models
+        .iter()
+        .map(|model| {
+            if model.is_view {
+                format!("{} (view)", model.name)
+            } else {
+                model.name.clone()
+            }
+        })
         .collect();
 
-    let module_selection = FuzzySelect::with_theme(&ColorfulTheme::default())
-        .with_prompt("Select output module")
+    let model_selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select model")
         .default(0)
-        .items(&modules)
-        .interact()
-        .unwrap();
+        .items(&model_names)
+        .interact()?;
 
-    let selected_module = modules.get(module_selection).unwrap();
+    let selected_model = models[model_selection];
 
-    let module_path = ts_config
-        .compiler_options
-        .paths
-        .get(&format!("@{}/*", selected_module))
-        .unwrap()
-        .first()
-        .unwrap()
-        .replace("*", "");
+    let root = match &args.out {
+        Some(out) => cwd.join(out),
+        None => select_output_root(&dir, &TsConfig::load(&dir)?, &config)?,
+    };
 
-    let multiselected: &[&str; 3] = &[
-        ModuleType::Entity.into(),
-        ModuleType::Mapper.into(),
-        ModuleType::Repository(None).into(),
-    ];
+    let selectable_modules = ModuleType::selectable(&config);
+    let multiselected: Vec<&str> = selectable_modules.iter().map(ModuleType::label).collect();
 
-    let defaults = &[true, false, false];
+    let defaults: Vec<bool> = multiselected
+        .iter()
+        .map(|module| {
+            config
+                .artifacts
+                .iter()
+                .any(|artifact| artifact.eq_ignore_ascii_case(module))
+        })
+        .collect();
 
     let selections = MultiSelect::with_theme(&ColorfulTheme::default())
         .with_prompt("Select which classes to create")
         .items(&multiselected[..])
         .defaults(&defaults[..])
-        .interact()
-        .unwrap();
+        .interact()?;
 
     let mut selected_modules: Vec<ModuleType> = selections
         .iter()
-        .map(|i| ModuleType::from(*multiselected.get(*i).unwrap()))
+        .map(|&i| selectable_modules[i].clone())
         .collect();
 
-    if selected_modules.contains(&ModuleType::Repository(None)) {
-        let methods: &[RepositoryOperations; 5] = &[
+    if let Some(index) = selected_modules
+        .iter()
+        .position(|item| *item == ModuleType::Repository(None))
+    {
+        let methods: Vec<RepositoryOperations> = [
             RepositoryOperations::Find,
             RepositoryOperations::FindMany,
             RepositoryOperations::Create,
             RepositoryOperations::Delete,
             RepositoryOperations::Update,
-        ];
+            RepositoryOperations::Count,
+        ]
+        .into_iter()
+        .filter(|method| !selected_model.is_view || method.is_read_only())
+        .collect();
+
+        let method_defaults: Vec<bool> = methods
+            .iter()
+            .map(|method| config.repository_operations.contains(method))
+            .collect();
 
         let selections = MultiSelect::with_theme(&ColorfulTheme::default())
             .with_prompt("Select which repository methods to create")
             .items(&methods[..])
-            .defaults(&defaults[..])
-            .interact()
-            .unwrap();
+            .defaults(&method_defaults[..])
+            .interact()?;
 
-        let selected_repositories: Vec<RepositoryOperations> = selections
-            .iter()
-            .map(|&i| methods.get(i).unwrap().clone())
-            .collect();
-
-        let index = selected_modules
-            .iter()
-            .position(|item| *item == ModuleType::Repository(None))
-            .unwrap();
+        let selected_repositories: Vec<RepositoryOperations> =
+            selections.iter().map(|&i| methods[i].clone()).collect();
 
         selected_modules[index] = ModuleType::Repository(Some(selected_repositories))
     };
 
-    write_modules(selected_modules, &dir, &module_path, selected_model)
+    let templates = Templates::new(dir.join(&config.templates_dir));
+    let files = generate_modules(
+        selected_modules,
+        &root,
+        selected_model,
+        &schema,
+        &config,
+        &templates,
+    )?;
+
+    let written = write_modules(
+        &files,
+        &Writer {
+            force: args.force,
+            dry_run: args.dry_run,
+            barrels: config.barrels,
+        },
+        &mut Manifest::load(&dir)?,
+    )?;
+
+    register_repositories(&written, &dir, &config, &templates, args.dry_run)
 }
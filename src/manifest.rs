@@ -0,0 +1,114 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+use crate::regions::strip_regions;
+
+pub const MANIFEST_FILE: &str = ".entity-generator/manifest.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// The model, view or composite type the file was generated from. Unset
+    /// for schema-wide artifacts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub artifact: String,
+    /// SHA-256 of the generated contents, without the contents of custom
+    /// regions.
+    pub hash: String,
+}
+
+/// Records every file the generator wrote, keyed by its path relative to the
+/// project root.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(skip)]
+    root: PathBuf,
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+fn hash(contents: &str) -> String {
+    Sha256::digest(strip_regions(contents).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+impl Manifest {
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(MANIFEST_FILE);
+
+        let mut manifest: Manifest = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|err| {
+                Error::InvalidConfig(format!(
+                    "invalid manifest at path {}: {}",
+                    path.display(),
+                    err
+                ))
+            })?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Manifest::default(),
+            Err(err) => return Err(Error::io(path)(err)),
+        };
+
+        manifest.root = root.to_path_buf();
+        Ok(manifest)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = self.root.join(MANIFEST_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(Error::io(parent))?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::from);
+        content
+            .and_then(|content| fs::write(&path, content + "\n"))
+            .map_err(Error::io(path))
+    }
+
+    fn key(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    pub fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+
+    pub fn record(&mut self, path: &Path, model: Option<&str>, artifact: &str, contents: &str) {
+        self.files.insert(
+            self.key(path),
+            ManifestEntry {
+                model: model.map(str::to_string),
+                artifact: artifact.to_string(),
+                hash: hash(contents),
+            },
+        );
+    }
+
+    /// Whether the file was written by the generator.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.contains_key(&self.key(path))
+    }
+
+    /// Whether the generated parts of the file were edited since it was last
+    /// written by the generator. Files missing from the manifest or from disk
+    /// are not considered modified.
+    pub fn is_modified(&self, path: &Path) -> bool {
+        let Some(entry) = self.files.get(&self.key(path)) else {
+            return false;
+        };
+
+        fs::read_to_string(path)
+            .map(|contents| hash(&contents) != entry.hash)
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,850 @@
+use std::{fs, io, path::Path};
+
+use serde::Serialize;
+
+use crate::code_gen::{import_path, GeneratedFile, ModuleType};
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::templates::{Templates, DATABASE_MODULE_TEMPLATE};
+use crate::writer::unified_diff;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DatabaseModuleContext {
+    class_name: String,
+}
+
+/// `DatabaseModule` for `database.module.ts`.
+fn module_class_name(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let name = name.split('.').next().unwrap_or_default();
+
+    name.split(['-', '_'])
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>()
+        + "Module"
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Whether `haystack` mentions the identifier `word` on its own.
+fn contains_word(haystack: &str, word: &str) -> bool {
+    haystack.match_indices(word).any(|(index, _)| {
+        let before = haystack[..index].chars().next_back();
+        let after = haystack[index + word.len()..].chars().next();
+        !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char)
+    })
+}
+
+/// Index of the bracket closing the one at `open`, skipping string literals.
+fn matching_bracket(content: &str, open: usize) -> Option<usize> {
+    let (opening, closing) = match content[open..].chars().next()? {
+        '[' => ('[', ']'),
+        '{' => ('{', '}'),
+        _ => return None,
+    };
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for (index, c) in content[open..].char_indices() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, c) if c == opening => depth += 1,
+            (None, c) if c == closing => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn line_indent(content: &str, index: usize) -> &str {
+    let line_start = content[..index].rfind('\n').map_or(0, |i| i + 1);
+    let line = &content[line_start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Braces of the object literal passed to `@Module(...)`.
+fn module_object(content: &str) -> Option<(usize, usize)> {
+    let decorator = content.find("@Module(")?;
+    let open = decorator + content[decorator..].find('{')?;
+    Some((open, matching_bracket(content, open)?))
+}
+
+/// Indentation of the module properties and of a single level, taken from the
+/// file when the module has properties already.
+fn module_indent(content: &str, open: usize, unit: &str) -> (String, String) {
+    let base = line_indent(content, open);
+    let property = content[open + 1..]
+        .lines()
+        .skip(1)
+        .find(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .filter(|indent| indent.len() > base.len() && indent.starts_with(base));
+
+    match property {
+        Some(indent) => (indent.to_string(), indent[base.len()..].to_string()),
+        None => (format!("{}{}", base, unit), unit.to_string()),
+    }
+}
+
+/// Index of the `key` property among the top-level properties of the object
+/// whose braces are at `open` and `close`. Nested objects, arrays, calls and
+/// strings are skipped, so the `providers` of a dynamic module passed to
+/// `imports` are not mistaken for the module's own.
+fn top_level_property(content: &str, open: usize, close: usize, key: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for (index, c) in content[open + 1..close].char_indices() {
+        let index = open + 1 + index;
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, _) if depth == 0 && content[index..].starts_with(key) => {
+                let before = content[..index].chars().next_back();
+                let after = content[index + key.len()..].trim_start();
+                if !before.is_some_and(is_ident_char) && after.starts_with(':') {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Byte range between the brackets of the `key: [...]` array of the module
+/// decorator.
+fn find_array(content: &str, key: &str) -> Option<(usize, usize)> {
+    let (open, close) = module_object(content)?;
+    let start = top_level_property(content, open, close, key)?;
+    let colon = start + content[start..].find(':')?;
+    let value = content[colon + 1..].trim_start();
+    if !value.starts_with('[') {
+        return None;
+    }
+
+    let bracket = content.len() - value.len();
+    Some((bracket, matching_bracket(content, bracket)?))
+}
+
+/// Adds `element` to the `key` array of the module decorator, creating the
+/// array after the last property when the decorator has none. Elements
+/// already present, according to `is_present`, are not added again. Returns
+/// `None` when there is no decorator or `key` holds something other than an
+/// array literal.
+fn add_to_array(
+    content: &mut String,
+    key: &str,
+    element: &str,
+    unit: &str,
+    is_present: impl Fn(&str) -> bool,
+) -> Option<()> {
+    let (open, close) = module_object(content)?;
+    let (property_indent, unit) = module_indent(content, open, unit);
+
+    if top_level_property(content, open, close, key).is_none() {
+        let inner = &content[open + 1..close];
+        let last = open + 1 + inner.trim_end().len();
+        if inner.trim().is_empty() {
+            let indent = line_indent(content, open).to_string();
+            let property = format!("\n{}{}: [],\n{}", property_indent, key, indent);
+            content.replace_range(open + 1..close, &property);
+        } else {
+            let comma = if inner.trim_end().ends_with(',') {
+                ""
+            } else {
+                ","
+            };
+            let property = format!("{}\n{}{}: [],", comma, property_indent, key);
+            content.insert_str(last, &property);
+        }
+    }
+
+    let (open, close) = find_array(content, key)?;
+    let inner = &content[open + 1..close];
+    if is_present(inner) {
+        return Some(());
+    }
+
+    let indent = line_indent(content, open).to_string();
+    let last = open + 1 + inner.trim_end().len();
+    if inner.trim().is_empty() {
+        let replacement = format!("\n{}{}{},\n{}", indent, unit, element, indent);
+        content.replace_range(open + 1..close, &replacement);
+    } else if inner.contains('\n') {
+        let comma = if inner.trim_end().ends_with(',') {
+            ""
+        } else {
+            ","
+        };
+        let insertion = format!("{}\n{}{}{},", comma, indent, unit, element);
+        content.insert_str(last, &insertion);
+    } else {
+        let separator = if inner.trim_end().ends_with(',') {
+            " "
+        } else {
+            ", "
+        };
+        content.insert_str(last, &format!("{}{}", separator, element));
+    }
+
+    Some(())
+}
+
+/// Byte indices of the `import` statements, each spanning from its keyword to
+/// the end of the line holding its module specifier, which may follow a
+/// multi-line list of names.
+fn import_statements(content: &str) -> Vec<(usize, usize)> {
+    content
+        .match_indices("import ")
+        .filter(|(index, _)| *index == 0 || content[..*index].ends_with('\n'))
+        .map(|(index, _)| {
+            let specifier = content[index..]
+                .find(['\'', '"'])
+                .and_then(|quote| {
+                    let start = index + quote;
+                    let closing = content[start..].chars().next()?;
+                    content[start + 1..]
+                        .find(closing)
+                        .map(|end| start + 1 + end)
+                })
+                .unwrap_or(index);
+            let end = content[specifier..]
+                .find('\n')
+                .map_or(content.len(), |end| specifier + end);
+            (index, end)
+        })
+        .collect()
+}
+
+/// Adds an import of `name` unless the file already imports it.
+fn add_import(content: &mut String, name: &str, from: &str) {
+    let statements = import_statements(content);
+    if statements
+        .iter()
+        .any(|&(start, end)| contains_word(&content[start..end], name))
+    {
+        return;
+    }
+
+    // Follows the semicolon style of the existing imports.
+    let import = |semicolon: &str| format!("import {{ {} }} from '{}'{}", name, from, semicolon);
+    match statements.last() {
+        Some(&(start, end)) => {
+            let semicolon = if content[start..end].trim_end().ends_with(';') {
+                ";"
+            } else {
+                ""
+            };
+            content.insert_str(end, &format!("\n{}", import(semicolon)));
+        }
+        None => content.insert_str(0, &format!("{}\n", import(""))),
+    }
+}
+
+/// Imports `name` and `prisma_name` from the given specifiers and registers
+/// them as a provider and an export of the module in `content`.
+fn register(
+    content: &mut String,
+    (name, name_from): (&str, &str),
+    (prisma_name, prisma_name_from): (&str, &str),
+    unit: &str,
+) -> Option<()> {
+    module_object(content)?;
+    add_import(content, name, name_from);
+    add_import(content, prisma_name, prisma_name_from);
+
+    let provider = format!("{{ provide: {}, useClass: {} }}", name, prisma_name);
+    add_to_array(content, "providers", &provider, unit, |inner| {
+        contains_word(inner, &format!("provide: {}", name))
+    })?;
+    add_to_array(content, "exports", name, unit, |inner| {
+        contains_word(inner, name)
+    })
+}
+
+/// Byte ranges of the non-empty, comma separated elements between the brackets
+/// at `open` and `close`, without surrounding whitespace.
+fn list_elements(content: &str, open: usize, close: usize) -> Vec<(usize, usize)> {
+    let mut elements = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let text = &content[start..end];
+        let trimmed_start = start + (text.len() - text.trim_start().len());
+        let trimmed_end = start + text.trim_end().len();
+        if trimmed_start < trimmed_end {
+            elements.push((trimmed_start, trimmed_end));
+        }
+    };
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut start = open + 1;
+
+    for (index, c) in content[open + 1..close].char_indices() {
+        let index = open + 1 + index;
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                push(start, index);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    push(start, close);
+
+    elements
+}
+
+/// Removes the first element between the brackets at `open` and `close` that
+/// matches `is_target`, along with its comma, and its line when it has one of
+/// its own. Returns whether an element was removed.
+fn remove_element(
+    content: &mut String,
+    open: usize,
+    close: usize,
+    is_target: impl Fn(&str) -> bool,
+) -> bool {
+    let elements = list_elements(content, open, close);
+    let Some(index) = elements
+        .iter()
+        .position(|&(start, end)| is_target(&content[start..end]))
+    else {
+        return false;
+    };
+
+    let (start, end) = elements[index];
+    let after = &content[end..close];
+    let comma_end = after
+        .trim_start()
+        .starts_with(',')
+        .then(|| end + (after.len() - after.trim_start().len()) + 1);
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let element_end = comma_end.unwrap_or(end);
+    let line_end = content[element_end..close]
+        .find('\n')
+        .map(|newline| element_end + newline + 1)
+        .filter(|&line_end| content[element_end..line_end].trim().is_empty());
+
+    let range = match (line_end, comma_end) {
+        (Some(line_end), _)
+            if line_start > open && content[line_start..start].trim().is_empty() =>
+        {
+            line_start..line_end
+        }
+        (_, Some(comma_end)) => {
+            let rest = &content[comma_end..close];
+            start..comma_end + (rest.len() - rest.trim_start_matches(' ').len())
+        }
+        _ if index > 0 => elements[index - 1].1..end,
+        _ => start..end,
+    };
+
+    content.replace_range(range, "");
+
+    // An emptied multi-line list collapses to `[]`.
+    if let Some(close) = matching_bracket(content, open) {
+        if content[open + 1..close].trim().is_empty() {
+            content.replace_range(open + 1..close, "");
+        }
+    }
+
+    true
+}
+
+/// Removes `name` from the named imports of the file, dropping the import
+/// statement when nothing else is imported by it.
+fn remove_import(content: &mut String, name: &str) {
+    for (start, end) in import_statements(content).into_iter().rev() {
+        let Some(open) = content[start..end].find('{').map(|open| start + open) else {
+            continue;
+        };
+        let Some(close) = matching_bracket(content, open) else {
+            continue;
+        };
+
+        let removed = remove_element(content, open, close, |element| {
+            element == name || element.ends_with(&format!(" as {}", name))
+        });
+        if !removed {
+            continue;
+        }
+
+        let close = matching_bracket(content, open).unwrap_or(open);
+        let is_empty = content[open + 1..close].trim().is_empty();
+        if is_empty && content[start..open].trim() == "import" {
+            let end = content[close..]
+                .find('\n')
+                .map_or(content.len(), |newline| close + newline + 1);
+            content.replace_range(start..end, "");
+        }
+
+        return;
+    }
+}
+
+/// Whether `name` is still used outside of the import statements.
+fn is_referenced(content: &str, name: &str) -> bool {
+    let mut rest = content.to_string();
+    for (start, end) in import_statements(content).into_iter().rev() {
+        rest.replace_range(start..end, "");
+    }
+
+    contains_word(&rest, name)
+}
+
+/// Removes the provider and the export of `name` from the module in
+/// `content`, and the imports of `name` and `prisma_name` that are no longer
+/// used.
+fn unregister(content: &mut String, name: &str, prisma_name: &str) {
+    if let Some((open, close)) = find_array(content, "providers") {
+        remove_element(content, open, close, |element| {
+            contains_word(element, &format!("provide: {}", name))
+        });
+    }
+    if let Some((open, close)) = find_array(content, "exports") {
+        remove_element(content, open, close, |element| element == name);
+    }
+
+    for name in [name, prisma_name] {
+        if !is_referenced(content, name) {
+            remove_import(content, name);
+        }
+    }
+}
+
+/// Registers `{ provide: {Model}Repository, useClass: Prisma{Model}Repository }`
+/// and exports the repository in the configured NestJS module for every
+/// repository among `files` that was written along with its implementation.
+pub fn register_repositories(
+    files: &[&GeneratedFile],
+    dir: &Path,
+    config: &Config,
+    templates: &Templates,
+    dry_run: bool,
+) -> Result<()> {
+    let Some(module) = &config.database_module else {
+        return Ok(());
+    };
+    let path = dir.join(module);
+    let module_dir = path.parent().unwrap_or(dir);
+    let unit = config.formatting.indent.unit();
+
+    let repositories: Vec<(&GeneratedFile, &GeneratedFile)> = files
+        .iter()
+        .copied()
+        .filter(|file| file.artifact == ModuleType::Repository(None))
+        .filter_map(|repository| {
+            let prisma_repository = files.iter().copied().find(|file| {
+                file.artifact == ModuleType::PrismaRepository && file.model == repository.model
+            })?;
+            Some((repository, prisma_repository))
+        })
+        .collect();
+
+    if repositories.is_empty() {
+        return Ok(());
+    }
+
+    let original = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(Error::io(&path)(err)),
+    };
+
+    let mut content = if original.is_empty() {
+        config.formatting.apply(templates.render(
+            DATABASE_MODULE_TEMPLATE,
+            DatabaseModuleContext {
+                class_name: module_class_name(&path),
+            },
+        )?)
+    } else {
+        original.clone()
+    };
+
+    for (repository, prisma_repository) in repositories {
+        let model = repository.model.as_deref().unwrap_or_default();
+        let name = config.naming.repository(model);
+        let prisma_name = config.naming.prisma_repository(model);
+
+        let registered = register(
+            &mut content,
+            (&name, &import_path(module_dir, &repository.path)),
+            (
+                &prisma_name,
+                &import_path(module_dir, &prisma_repository.path),
+            ),
+            &unit,
+        );
+
+        if registered.is_none() {
+            return Err(Error::InvalidConfig(format!(
+                "{} needs a @Module decorator whose providers and exports are array literals to register {} in",
+                path.display(),
+                name
+            )));
+        }
+    }
+
+    if content == original {
+        return Ok(());
+    }
+
+    if dry_run {
+        print!("{}", unified_diff(&path, &original, &content));
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(Error::io(parent))?;
+    }
+    fs::write(&path, content).map_err(Error::io(&path))?;
+    println!("{} (registered repositories)", path.display());
+
+    Ok(())
+}
+
+/// Removes the repository of `model` from the configured NestJS module, after
+/// its files were deleted.
+pub fn unregister_repository(
+    dir: &Path,
+    config: &Config,
+    model: &str,
+    dry_run: bool,
+) -> Result<()> {
+    let Some(module) = &config.database_module else {
+        return Ok(());
+    };
+    let path = dir.join(module);
+    let original = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(Error::io(&path)(err)),
+    };
+
+    let name = config.naming.repository(model);
+    let mut content = original.clone();
+    unregister(&mut content, &name, &config.naming.prisma_repository(model));
+
+    if content == original {
+        return Ok(());
+    }
+
+    if dry_run {
+        print!("{}", unified_diff(&path, &original, &content));
+        return Ok(());
+    }
+
+    fs::write(&path, content).map_err(Error::io(&path))?;
+    println!("{} (unregistered {})", path.display(), name);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register_user(content: &str) -> String {
+        let mut content = content.to_string();
+        register(
+            &mut content,
+            ("UserRepository", "./user.repository"),
+            ("PrismaUserRepository", "./prisma-user.repository"),
+            "  ",
+        )
+        .unwrap();
+        content
+    }
+
+    #[test]
+    fn registers_in_module_not_in_nested_dynamic_module() {
+        let content = "\
+import { Module } from '@nestjs/common'
+
+@Module({
+  imports: [CacheModule.register({ providers: [A], exports: [A] })],
+  extraProviders: [B],
+})
+export class DatabaseModule {}
+";
+
+        assert_eq!(
+            register_user(content),
+            "\
+import { Module } from '@nestjs/common'
+import { UserRepository } from './user.repository'
+import { PrismaUserRepository } from './prisma-user.repository'
+
+@Module({
+  imports: [CacheModule.register({ providers: [A], exports: [A] })],
+  extraProviders: [B],
+  providers: [
+    { provide: UserRepository, useClass: PrismaUserRepository },
+  ],
+  exports: [
+    UserRepository,
+  ],
+})
+export class DatabaseModule {}
+"
+        );
+    }
+
+    #[test]
+    fn adds_missing_arrays_with_file_indentation() {
+        let content = "\
+import { Module } from '@nestjs/common';
+import {
+    ConfigModule,
+} from '@nestjs/config';
+
+@Module({
+    imports: [ConfigModule]
+})
+export class DatabaseModule {}
+";
+
+        assert_eq!(
+            register_user(content),
+            "\
+import { Module } from '@nestjs/common';
+import {
+    ConfigModule,
+} from '@nestjs/config';
+import { UserRepository } from './user.repository';
+import { PrismaUserRepository } from './prisma-user.repository';
+
+@Module({
+    imports: [ConfigModule],
+    providers: [
+        { provide: UserRepository, useClass: PrismaUserRepository },
+    ],
+    exports: [
+        UserRepository,
+    ],
+})
+export class DatabaseModule {}
+"
+        );
+    }
+
+    #[test]
+    fn appends_to_existing_arrays() {
+        let content = "\
+import { Module } from '@nestjs/common'
+
+@Module({
+  providers: [PrismaService],
+  exports: [
+    PrismaService,
+  ],
+})
+export class DatabaseModule {}
+";
+
+        assert_eq!(
+            register_user(content),
+            "\
+import { Module } from '@nestjs/common'
+import { UserRepository } from './user.repository'
+import { PrismaUserRepository } from './prisma-user.repository'
+
+@Module({
+  providers: [PrismaService, { provide: UserRepository, useClass: PrismaUserRepository }],
+  exports: [
+    PrismaService,
+    UserRepository,
+  ],
+})
+export class DatabaseModule {}
+"
+        );
+    }
+
+    #[test]
+    fn registering_again_changes_nothing() {
+        let content = "\
+import { Module } from '@nestjs/common'
+import {
+  UserRepository,
+} from './user.repository'
+import { PrismaUserRepository } from './prisma-user.repository'
+
+@Module({
+  providers: [{ provide: UserRepository, useClass: PrismaUserRepository }],
+  exports: [UserRepository],
+})
+export class DatabaseModule {}
+";
+
+        assert_eq!(register_user(content), content);
+        assert_eq!(
+            register_user(&register_user("@Module({})")),
+            register_user("@Module({})")
+        );
+    }
+
+    #[test]
+    fn rejects_modules_it_cannot_edit() {
+        for content in [
+            "export class DatabaseModule {}",
+            "@Module({ providers: shared })",
+        ] {
+            let mut content = content.to_string();
+            assert!(register(&mut content, ("A", "./a"), ("B", "./b"), "  ").is_none());
+        }
+    }
+
+    fn unregister_user(content: &str) -> String {
+        let mut content = content.to_string();
+        unregister(&mut content, "UserRepository", "PrismaUserRepository");
+        content
+    }
+
+    #[test]
+    fn unregistering_undoes_registering() {
+        let content = "\
+import { Module } from '@nestjs/common'
+import { PostRepository } from './post.repository'
+
+@Module({
+  providers: [
+    { provide: PostRepository, useClass: PrismaPostRepository },
+  ],
+  exports: [
+    PostRepository,
+  ],
+})
+export class DatabaseModule {}
+";
+
+        assert_eq!(unregister_user(&register_user(content)), content);
+    }
+
+    #[test]
+    fn unregisters_from_inline_arrays_and_shared_imports() {
+        let content = "\
+import { Module } from '@nestjs/common'
+import { PostRepository, UserRepository } from './repositories'
+import { PrismaUserRepository } from './prisma-user.repository'
+
+@Module({
+  providers: [
+    { provide: UserRepository, useClass: PrismaUserRepository }, PostRepository,
+  ],
+  exports: [PostRepository, UserRepository],
+})
+export class DatabaseModule {}
+";
+
+        assert_eq!(
+            unregister_user(content),
+            "\
+import { Module } from '@nestjs/common'
+import { PostRepository } from './repositories'
+
+@Module({
+  providers: [
+    PostRepository,
+  ],
+  exports: [PostRepository],
+})
+export class DatabaseModule {}
+"
+        );
+    }
+
+    #[test]
+    fn keeps_imports_still_in_use() {
+        let content = "\
+import { Module } from '@nestjs/common'
+import { UserRepository } from './user.repository'
+import { PrismaUserRepository } from './prisma-user.repository'
+
+@Module({
+  providers: [{ provide: UserRepository, useClass: PrismaUserRepository }, UserService],
+  exports: [UserRepository],
+})
+export class DatabaseModule {
+  constructor(readonly users: UserRepository) {}
+}
+";
+
+        assert_eq!(
+            unregister_user(content),
+            "\
+import { Module } from '@nestjs/common'
+import { UserRepository } from './user.repository'
+
+@Module({
+  providers: [UserService],
+  exports: [],
+})
+export class DatabaseModule {
+  constructor(readonly users: UserRepository) {}
+}
+"
+        );
+    }
+
+    #[test]
+    fn names_and_import_paths() {
+        assert_eq!(
+            module_class_name(Path::new("src/infra/database.module.ts")),
+            "DatabaseModule"
+        );
+        assert_eq!(
+            module_class_name(Path::new("prisma-data.module.ts")),
+            "PrismaDataModule"
+        );
+        assert_eq!(
+            import_path(
+                Path::new("src/infra"),
+                Path::new("src/users/user.repository.ts")
+            ),
+            "../users/user.repository"
+        );
+        assert_eq!(
+            import_path(Path::new("src"), Path::new("src/infra/user.repository.ts")),
+            "./infra/user.repository"
+        );
+    }
+}
@@ -1,117 +1,991 @@
 use std::{
-    collections::HashMap,
-    fs::{self, File},
-    io::{self, BufRead, BufReader},
-    path::PathBuf,
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
 };
 
-use serde::Deserialize;
+use serde::Serialize;
 
-#[derive(Debug, Deserialize)]
-pub struct TsConfigCompilerOptions {
-    pub paths: HashMap<String, Vec<String>>,
+use crate::error::Error;
+use crate::lexer::{tokenize, ParseError, Span, Token, TokenKind};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum Expression {
+    String(String),
+    Number(String),
+    Constant(String),
+    Function {
+        name: String,
+        arguments: Vec<AttributeArgument>,
+    },
+    Array(Vec<Expression>),
+}
+
+impl Expression {
+    fn as_names(&self) -> Vec<String> {
+        match self {
+            Expression::Array(values) => values.iter().flat_map(Expression::as_names).collect(),
+            Expression::Constant(name) => vec![name.clone()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttributeArgument {
+    pub name: Option<String>,
+    pub value: Expression,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Attribute {
+    pub name: String,
+    pub arguments: Vec<AttributeArgument>,
+    pub span: Span,
+}
+
+impl Attribute {
+    /// Returns the named argument, or the first positional argument when
+    /// `name` is the attribute's default argument.
+    pub fn argument(&self, name: &str) -> Option<&Expression> {
+        self.arguments
+            .iter()
+            .find(|argument| argument.name.as_deref() == Some(name))
+            .or_else(|| {
+                self.arguments
+                    .first()
+                    .filter(|argument| argument.name.is_none())
+            })
+            .map(|argument| &argument.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldKind {
+    Scalar,
+    Enum,
+    Relation,
+    Composite,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct TsConfig {
-    #[serde(rename(deserialize = "compilerOptions"))]
-    pub compiler_options: TsConfigCompilerOptions,
+#[derive(Debug, Clone, Serialize)]
+pub struct Relation {
+    pub name: Option<String>,
+    pub model: String,
+    pub fields: Vec<String>,
+    pub references: Vec<String>,
 }
 
-impl TsConfig {
-    pub fn default() -> Self {
-        let mut paths = HashMap::new();
-        paths.insert("@src/*".to_string(), Vec::from(["src/*".to_string()]));
-        TsConfig {
-            compiler_options: TsConfigCompilerOptions { paths },
+impl Relation {
+    fn from_field(field: &Field) -> Self {
+        let relation = field.attribute("relation");
+        let names = |argument: &str| {
+            relation
+                .and_then(|relation| {
+                    relation
+                        .arguments
+                        .iter()
+                        .find(|arg| arg.name.as_deref() == Some(argument))
+                })
+                .map(|arg| arg.value.as_names())
+                .unwrap_or_default()
+        };
+
+        Relation {
+            name: relation.and_then(|relation| match relation.argument("name") {
+                Some(Expression::String(name)) => Some(name.clone()),
+                _ => None,
+            }),
+            model: field.field_type.clone(),
+            fields: names("fields"),
+            references: names("references"),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Field {
     pub name: String,
+    #[serde(rename = "type")]
     pub field_type: String,
+    pub kind: FieldKind,
     pub is_optional: bool,
+    pub is_list: bool,
+    pub attributes: Vec<Attribute>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<Relation>,
+    /// Column name from `@map`, or the field name when it isn't mapped.
+    pub db_name: String,
+    pub span: Span,
+}
+
+impl Field {
+    pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Model {
     pub name: String,
     pub fields: Vec<Field>,
+    pub attributes: Vec<Attribute>,
+    /// Views are parsed like models but can only be read from.
+    #[serde(skip)]
+    pub is_view: bool,
+    /// Table name from `@@map`, or the model name when it isn't mapped.
+    pub db_name: String,
+    pub span: Span,
+}
+
+/// An embedded `type` block, as used by the MongoDB connector.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompositeType {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub span: Span,
 }
 
-pub fn parse_schema(reader: BufReader<File>) -> Vec<Model> {
-    let mut lines = reader.lines().peekable();
-    let mut models = Vec::new();
+#[derive(Debug, Clone, Serialize)]
+pub struct EnumValue {
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Enum {
+    pub name: String,
+    pub values: Vec<EnumValue>,
+    pub attributes: Vec<Attribute>,
+    pub span: Span,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Schema {
+    pub models: Vec<Model>,
+    pub views: Vec<Model>,
+    pub enums: Vec<Enum>,
+    pub types: Vec<CompositeType>,
+}
 
-    while let Some(Ok(line)) = lines.next() {
-        let line = line.trim();
+impl Schema {
+    /// Merges the blocks of another file of a multi-file schema into this one.
+    /// Blocks clashing with the ones already merged are reported with the span
+    /// in `other`.
+    pub fn extend(&mut self, other: Schema) -> Result<(), ParseError> {
+        self.models.extend(other.models);
+        self.views.extend(other.views);
+        self.enums.extend(other.enums);
+        self.types.extend(other.types);
+        validate_schema(self)?;
+        self.resolve_fields();
 
-        if line.starts_with("model") {
-            let model_name = line.split_whitespace().nth(1).unwrap().to_string();
-            let mut fields = Vec::new();
+        Ok(())
+    }
 
-            while let Some(Ok(field_line)) = lines.peek() {
-                let field_line = field_line.trim();
-                if field_line == "}" {
-                    lines.next();
-                    break;
+    /// Models and views, in the order they should be offered for generation.
+    pub fn entities(&self) -> impl Iterator<Item = &Model> {
+        self.models.iter().chain(&self.views)
+    }
+
+    pub fn composite_type(&self, name: &str) -> Option<&CompositeType> {
+        self.types.iter().find(|composite| composite.name == name)
+    }
+
+    /// Classifies every field as scalar, enum, composite or relation now that
+    /// all the blocks it may refer to are known.
+    fn resolve_fields(&mut self) {
+        let model_names: HashSet<String> =
+            self.entities().map(|model| model.name.clone()).collect();
+        let enum_names: HashSet<String> = self.enums.iter().map(|en| en.name.clone()).collect();
+        let type_names: HashSet<String> = self
+            .types
+            .iter()
+            .map(|composite| composite.name.clone())
+            .collect();
+
+        let fields = self
+            .models
+            .iter_mut()
+            .chain(self.views.iter_mut())
+            .flat_map(|model| &mut model.fields)
+            .chain(
+                self.types
+                    .iter_mut()
+                    .flat_map(|composite| &mut composite.fields),
+            );
+
+        for field in fields {
+            if model_names.contains(&field.field_type) {
+                field.relation = Some(Relation::from_field(field));
+                field.kind = FieldKind::Relation;
+            } else if enum_names.contains(&field.field_type) {
+                field.kind = FieldKind::Enum;
+            } else if type_names.contains(&field.field_type) {
+                field.kind = FieldKind::Composite;
+            }
+        }
+    }
+}
+
+fn mapped_name(attributes: &[Attribute], name: &str) -> String {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name == "map")
+        .and_then(|attribute| match attribute.argument("name") {
+            Some(Expression::String(mapped)) => Some(mapped.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| name.to_string())
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        if token.kind != TokenKind::Eof {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if &self.peek().kind == kind {
+            self.next();
+            return true;
+        }
+
+        false
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.eat(&TokenKind::Newline) {}
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        let token = self.peek();
+        ParseError::new(
+            format!("expected {}, found {}", expected, token.kind),
+            token.span,
+        )
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token, ParseError> {
+        if self.peek().kind == kind {
+            return Ok(self.next());
+        }
+
+        Err(self.unexpected(&kind.to_string()))
+    }
+
+    fn expect_ident(&mut self, expected: &str) -> Result<(String, Span), ParseError> {
+        match self.peek().kind.clone() {
+            TokenKind::Ident(ident) => Ok((ident, self.next().span)),
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn expect_end_of_line(&mut self) -> Result<(), ParseError> {
+        match self.peek().kind {
+            TokenKind::Newline => {
+                self.next();
+                Ok(())
+            }
+            TokenKind::RBrace | TokenKind::Eof => Ok(()),
+            _ => Err(self.unexpected("end of line")),
+        }
+    }
+
+    fn dotted_ident(&mut self, expected: &str) -> Result<(String, Span), ParseError> {
+        let (mut name, span) = self.expect_ident(expected)?;
+
+        while self.eat(&TokenKind::Dot) {
+            let (part, _) = self.expect_ident("identifier after `.`")?;
+            name.push('.');
+            name.push_str(&part);
+        }
+
+        Ok((name, span))
+    }
+
+    fn expression(&mut self) -> Result<Expression, ParseError> {
+        self.skip_newlines();
+
+        match self.peek().kind.clone() {
+            TokenKind::String(value) => {
+                self.next();
+                Ok(Expression::String(value))
+            }
+            TokenKind::Number(value) => {
+                self.next();
+                Ok(Expression::Number(value))
+            }
+            TokenKind::LBracket => {
+                self.next();
+                let mut values = Vec::new();
+
+                loop {
+                    self.skip_newlines();
+                    if self.eat(&TokenKind::RBracket) {
+                        break;
+                    }
+
+                    values.push(self.expression()?);
+                    self.skip_newlines();
+
+                    if !self.eat(&TokenKind::Comma) {
+                        self.skip_newlines();
+                        self.expect(TokenKind::RBracket)?;
+                        break;
+                    }
                 }
 
-                if let Some(field) = parse_field(field_line) {
-                    fields.push(field);
+                Ok(Expression::Array(values))
+            }
+            TokenKind::Ident(_) => {
+                let (name, _) = self.dotted_ident("expression")?;
+
+                if self.peek().kind == TokenKind::LParen {
+                    return Ok(Expression::Function {
+                        name,
+                        arguments: self.arguments()?,
+                    });
                 }
 
-                lines.next();
+                Ok(Expression::Constant(name))
             }
+            _ => Err(self.unexpected("expression")),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<AttributeArgument>, ParseError> {
+        self.expect(TokenKind::LParen)?;
+        let mut arguments = Vec::new();
+
+        loop {
+            self.skip_newlines();
+            if self.eat(&TokenKind::RParen) {
+                break;
+            }
+
+            let is_named = matches!(self.peek().kind, TokenKind::Ident(_))
+                && self.tokens[self.position + 1].kind == TokenKind::Colon;
+
+            let name = if is_named {
+                let (name, _) = self.expect_ident("argument name")?;
+                self.next();
+                Some(name)
+            } else {
+                None
+            };
 
-            models.push(Model {
-                name: model_name,
-                fields,
+            arguments.push(AttributeArgument {
+                name,
+                value: self.expression()?,
             });
+            self.skip_newlines();
+
+            if !self.eat(&TokenKind::Comma) {
+                self.skip_newlines();
+                self.expect(TokenKind::RParen)?;
+                break;
+            }
         }
+
+        Ok(arguments)
     }
 
-    models
-}
+    fn attribute(&mut self) -> Result<Attribute, ParseError> {
+        let (name, span) = self.dotted_ident("attribute name")?;
+        let arguments = if self.peek().kind == TokenKind::LParen {
+            self.arguments()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Attribute {
+            name,
+            arguments,
+            span,
+        })
+    }
+
+    fn field_attributes(&mut self) -> Result<Vec<Attribute>, ParseError> {
+        let mut attributes = Vec::new();
 
-fn parse_field(line: &str) -> Option<Field> {
-    let parts: Vec<&str> = line.split_whitespace().collect();
+        while self.eat(&TokenKind::At) {
+            attributes.push(self.attribute()?);
+        }
+
+        Ok(attributes)
+    }
 
-    if parts.len() >= 2 {
-        let field_name = parts[0].to_string();
-        let mut field_type = parts[1].to_string();
-        let is_optional = field_type.ends_with('?');
+    fn field(&mut self) -> Result<Field, ParseError> {
+        let (name, span) = self.expect_ident("field name")?;
+        let (field_type, _) = match self.peek().kind {
+            TokenKind::Ident(_) => self.expect_ident("field type")?,
+            _ => return Err(self.unexpected(&format!("type for field `{}`", name))),
+        };
 
-        if is_optional {
-            field_type.pop();
+        if self.peek().kind == TokenKind::LParen {
+            self.arguments()?;
         }
 
-        return Some(Field {
-            name: field_name,
+        let is_list = if self.eat(&TokenKind::LBracket) {
+            self.expect(TokenKind::RBracket)?;
+            true
+        } else {
+            false
+        };
+        let is_optional = self.eat(&TokenKind::Question);
+
+        let attributes = self.field_attributes()?;
+        self.expect_end_of_line()?;
+
+        Ok(Field {
             field_type,
+            kind: FieldKind::Scalar,
             is_optional,
-        });
+            is_list,
+            relation: None,
+            db_name: mapped_name(&attributes, &name),
+            attributes,
+            name,
+            span,
+        })
     }
 
-    None
-}
+    fn block_header(&mut self, keyword: &str) -> Result<(String, Span), ParseError> {
+        let (name, span) = self.expect_ident(&format!("{} name", keyword))?;
+        self.skip_newlines();
+        self.expect(TokenKind::LBrace)?;
 
-pub fn get_schemas(path: String) -> Result<Vec<PathBuf>, io::Error> {
-    let entries = fs::read_dir(path)?;
+        Ok((name, span))
+    }
 
-    let file_paths: Vec<_> = entries
-        .filter_map(|entry| {
-            entry.ok().and_then(|e| {
-                if e.metadata().map(|m| m.is_file()).unwrap_or(false) {
-                    return Some(e.path());
+    /// Parses the members of a block until its closing brace, handing each
+    /// member line to `member` and collecting `@@` attributes.
+    fn block_body(
+        &mut self,
+        mut member: impl FnMut(&mut Self) -> Result<(), ParseError>,
+    ) -> Result<Vec<Attribute>, ParseError> {
+        let mut attributes = Vec::new();
+
+        loop {
+            self.skip_newlines();
+
+            match self.peek().kind {
+                TokenKind::RBrace => {
+                    self.next();
+                    return Ok(attributes);
                 }
+                TokenKind::AtAt => {
+                    self.next();
+                    attributes.push(self.attribute()?);
+                    self.expect_end_of_line()?;
+                }
+                TokenKind::Ident(_) => member(self)?,
+                TokenKind::Eof => return Err(self.unexpected("`}`")),
+                _ => return Err(self.unexpected("field or `}`")),
+            }
+        }
+    }
 
-                None
-            })
+    fn model(&mut self, keyword: &str) -> Result<Model, ParseError> {
+        let (name, span) = self.block_header(keyword)?;
+        let mut fields = Vec::new();
+        let attributes = self.block_body(|parser| {
+            fields.push(parser.field()?);
+            Ok(())
+        })?;
+
+        Ok(Model {
+            fields,
+            is_view: keyword == "view",
+            db_name: mapped_name(&attributes, &name),
+            attributes,
+            name,
+            span,
         })
-        .collect();
+    }
+
+    fn composite_type(&mut self) -> Result<CompositeType, ParseError> {
+        let (name, span) = self.block_header("type")?;
+        let mut fields = Vec::new();
+        let attributes = self.block_body(|parser| {
+            fields.push(parser.field()?);
+            Ok(())
+        })?;
+
+        if let Some(attribute) = attributes.first() {
+            return Err(ParseError::new(
+                format!("composite type `{}` cannot have block attributes", name),
+                attribute.span,
+            ));
+        }
+
+        Ok(CompositeType { name, fields, span })
+    }
 
-    Ok(file_paths)
+    fn enumeration(&mut self) -> Result<Enum, ParseError> {
+        let (name, span) = self.block_header("enum")?;
+        let mut values = Vec::new();
+        let attributes = self.block_body(|parser| {
+            let (name, span) = parser.expect_ident("enum value")?;
+            let attributes = parser.field_attributes()?;
+            parser.expect_end_of_line()?;
+
+            values.push(EnumValue {
+                name,
+                attributes,
+                span,
+            });
+            Ok(())
+        })?;
+
+        Ok(Enum {
+            name,
+            values,
+            attributes,
+            span,
+        })
+    }
+
+    /// Skips blocks the generator has no use for, such as `datasource` and
+    /// `generator`, by matching braces.
+    fn skip_block(&mut self, keyword: &str) -> Result<(), ParseError> {
+        self.block_header(keyword)?;
+        let mut depth = 1;
+
+        while depth > 0 {
+            match self.next().kind {
+                TokenKind::LBrace => depth += 1,
+                TokenKind::RBrace => depth -= 1,
+                TokenKind::Eof => return Err(self.unexpected("`}`")),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn schema(&mut self) -> Result<Schema, ParseError> {
+        let mut schema = Schema::default();
+
+        loop {
+            self.skip_newlines();
+
+            let (keyword, _) = match self.peek().kind {
+                TokenKind::Eof => return Ok(schema),
+                _ => self.expect_ident("block declaration")?,
+            };
+
+            match keyword.as_str() {
+                "model" => schema.models.push(self.model(&keyword)?),
+                "view" => schema.views.push(self.model(&keyword)?),
+                "enum" => schema.enums.push(self.enumeration()?),
+                "type" => schema.types.push(self.composite_type()?),
+                _ => self.skip_block(&keyword)?,
+            }
+        }
+    }
+}
+
+pub fn parse_schema(source: &str) -> Result<Schema, ParseError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+
+    let mut schema = parser.schema()?;
+    validate_schema(&schema)?;
+    schema.resolve_fields();
+
+    Ok(schema)
+}
+
+fn check_unique<'a>(
+    kind: &str,
+    names: impl Iterator<Item = (&'a str, Span)>,
+) -> Result<(), ParseError> {
+    let mut seen = HashSet::new();
+
+    for (name, span) in names {
+        if !seen.insert(name) {
+            return Err(ParseError::new(
+                format!("{} `{}` is defined more than once", kind, name),
+                span,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_map_attributes(attributes: &[Attribute]) -> Result<(), ParseError> {
+    for attribute in attributes
+        .iter()
+        .filter(|attribute| attribute.name == "map")
+    {
+        if !matches!(attribute.argument("name"), Some(Expression::String(_))) {
+            return Err(ParseError::new(
+                "`map` expects a string argument",
+                attribute.span,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_relation_fields(model: &Model, field: &Field) -> Result<(), ParseError> {
+    let Some(relation) = field.attribute("relation") else {
+        return Ok(());
+    };
+
+    for argument in ["fields", "references"] {
+        let Some(Expression::Array(values)) = relation
+            .arguments
+            .iter()
+            .find(|arg| arg.name.as_deref() == Some(argument))
+            .map(|arg| &arg.value)
+        else {
+            continue;
+        };
+
+        let is_local = argument == "fields";
+        for value in values {
+            let Expression::Constant(name) = value else {
+                return Err(ParseError::new(
+                    format!("`{}` of `relation` expects field names", argument),
+                    relation.span,
+                ));
+            };
+
+            if is_local && !model.fields.iter().any(|field| &field.name == name) {
+                return Err(ParseError::new(
+                    format!("unknown field `{}` in model `{}`", name, model.name),
+                    relation.span,
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_schema(schema: &Schema) -> Result<(), ParseError> {
+    check_unique(
+        "type",
+        schema
+            .entities()
+            .map(|model| (model.name.as_str(), model.span))
+            .chain(schema.enums.iter().map(|en| (en.name.as_str(), en.span)))
+            .chain(
+                schema
+                    .types
+                    .iter()
+                    .map(|composite| (composite.name.as_str(), composite.span)),
+            ),
+    )?;
+
+    for composite in &schema.types {
+        check_unique(
+            "field",
+            composite
+                .fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.span)),
+        )?;
+
+        for field in &composite.fields {
+            check_map_attributes(&field.attributes)?;
+        }
+    }
+
+    for model in schema.entities() {
+        check_unique(
+            "field",
+            model
+                .fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.span)),
+        )?;
+        check_map_attributes(&model.attributes)?;
+
+        for field in &model.fields {
+            check_map_attributes(&field.attributes)?;
+            check_relation_fields(model, field)?;
+        }
+    }
+
+    for en in &schema.enums {
+        check_unique(
+            "enum value",
+            en.values
+                .iter()
+                .map(|value| (value.name.as_str(), value.span)),
+        )?;
+        check_map_attributes(&en.attributes)?;
+
+        for value in &en.values {
+            check_map_attributes(&value.attributes)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn is_schema_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "prisma")
+}
+
+fn collect_schemas(dir: &Path, schemas: &mut Vec<PathBuf>) -> Result<(), io::Error> {
+    for entry in fs::read_dir(dir)?.filter_map(Result::ok) {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            if entry.file_name() != "migrations" {
+                collect_schemas(&path, schemas)?;
+            }
+        } else if file_type.is_file() && is_schema_file(&path) {
+            schemas.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn parse_schema_file(path: &Path) -> Result<Schema, Error> {
+    let content = fs::read_to_string(path).map_err(Error::io(path))?;
+
+    parse_schema(&content).map_err(|error| Error::Parse {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Whether the file declares a `datasource`, which every standalone schema and
+/// exactly one file of a multi-file schema does.
+fn declares_datasource(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| {
+        content.lines().any(|line| {
+            line.strip_prefix("datasource")
+                .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+        })
+    })
+}
+
+/// Groups the schema files found at `path` into schemas. A directory is a
+/// multi-file schema, loaded as one, unless more than one of its files
+/// declares a `datasource`; each file is a separate schema then.
+pub fn schema_sources(path: &Path) -> Result<Vec<Vec<PathBuf>>, io::Error> {
+    let schemas = get_schemas(path)?;
+    if schemas.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let datasources = schemas
+        .iter()
+        .filter(|schema| declares_datasource(schema))
+        .count();
+
+    if datasources > 1 {
+        Ok(schemas.into_iter().map(|schema| vec![schema]).collect())
+    } else {
+        Ok(vec![schemas])
+    }
+}
+
+/// Parses the files of one schema and merges them.
+pub fn parse_schema_files(paths: &[PathBuf]) -> Result<Schema, Error> {
+    let mut schema = Schema::default();
+    for path in paths {
+        schema
+            .extend(parse_schema_file(path)?)
+            .map_err(|error| Error::Parse {
+                path: path.to_path_buf(),
+                error,
+            })?;
+    }
+
+    Ok(schema)
+}
+
+/// Loads the schema at `path` for commands that do not ask which schema to
+/// use, failing when `path` holds several separate schemas.
+pub fn load_schema(path: &Path) -> Result<Schema, Error> {
+    let sources = schema_sources(path)
+        .ok()
+        .filter(|sources| !sources.is_empty())
+        .ok_or_else(|| Error::SchemaNotFound(path.to_path_buf()))?;
+
+    match sources.as_slice() {
+        [files] => parse_schema_files(files),
+        _ => Err(Error::Usage(format!(
+            "{} holds {} separate schemas, pick one with --schema",
+            path.display(),
+            sources.len()
+        ))),
+    }
+}
+
+pub fn get_schemas(path: &Path) -> Result<Vec<PathBuf>, io::Error> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut schemas = Vec::new();
+    collect_schemas(path, &mut schemas)?;
+    schemas.sort();
+
+    Ok(schemas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(source: &str) -> ParseError {
+        parse_schema(source).unwrap_err()
+    }
+
+    #[test]
+    fn parses_brace_without_space() {
+        let schema = parse_schema("model User{\n  id Int @id\n}\n").unwrap();
+
+        assert_eq!(schema.models[0].name, "User");
+        assert_eq!(schema.models[0].fields[0].name, "id");
+    }
+
+    #[test]
+    fn parses_brace_on_its_own_line() {
+        let schema =
+            parse_schema("model User\n{\n  id Int @id\n}\n\nenum Role\n{\n  ADMIN\n}\n").unwrap();
+
+        assert_eq!(schema.models[0].name, "User");
+        assert_eq!(schema.enums[0].values[0].name, "ADMIN");
+    }
+
+    #[test]
+    fn parses_multi_line_block_attribute() {
+        let schema = parse_schema(
+            "model User {\n  a Int\n  b Int\n\n  @@index([a,\n    b])\n  @@map(\"users\")\n}\n",
+        )
+        .unwrap();
+        let model = &schema.models[0];
+
+        assert_eq!(model.fields.len(), 2);
+        assert_eq!(model.attributes[0].name, "index");
+        assert_eq!(
+            model.attributes[0].argument("fields"),
+            Some(&Expression::Array(vec![
+                Expression::Constant("a".to_string()),
+                Expression::Constant("b".to_string()),
+            ]))
+        );
+        assert_eq!(model.db_name, "users");
+    }
+
+    #[test]
+    fn classifies_fields() {
+        let schema = parse_schema(
+            "model User {\n  id Int @id\n  role Role?\n  address Address\n  posts Post[]\n}\n\
+             model Post {\n  id Int @id\n  authorId Int\n  author User @relation(fields: [authorId], references: [id])\n}\n\
+             enum Role {\n  ADMIN\n}\n\
+             type Address {\n  street String\n}\n\
+             datasource db {\n  provider = \"mongodb\"\n  url = env(\"DB\")\n}\n",
+        )
+        .unwrap();
+        let kinds: Vec<(FieldKind, bool, bool)> = schema.models[0]
+            .fields
+            .iter()
+            .map(|field| (field.kind, field.is_optional, field.is_list))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                (FieldKind::Scalar, false, false),
+                (FieldKind::Enum, true, false),
+                (FieldKind::Composite, false, false),
+                (FieldKind::Relation, false, true),
+            ]
+        );
+
+        let relation = schema.models[1].fields[2].relation.as_ref().unwrap();
+        assert_eq!(relation.model, "User");
+        assert_eq!(relation.fields, ["authorId"]);
+        assert_eq!(relation.references, ["id"]);
+    }
+
+    #[test]
+    fn resolves_types_declared_in_other_files() {
+        let mut schema =
+            parse_schema("model User {\n  id Int @id\n  address Address\n}\n").unwrap();
+        assert_eq!(schema.models[0].fields[1].kind, FieldKind::Scalar);
+
+        schema
+            .extend(parse_schema("type Address {\n  street String\n}\n").unwrap())
+            .unwrap();
+        assert_eq!(schema.models[0].fields[1].kind, FieldKind::Composite);
+
+        let err = schema
+            .extend(parse_schema("\nmodel User {\n  id Int @id\n}\n").unwrap())
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 7));
+    }
+
+    #[test]
+    fn reports_missing_model_name() {
+        let err = error("model {\n  id Int @id\n}\n");
+
+        assert_eq!(err.message, "expected model name, found `{`");
+        assert_eq!((err.line, err.column), (1, 7));
+    }
+
+    #[test]
+    fn reports_unterminated_string() {
+        let err = error("model User {\n  name String @default(\"abc)\n}\n");
+
+        assert_eq!(err.message, "unterminated string literal");
+        assert_eq!((err.line, err.column), (2, 24));
+    }
+
+    #[test]
+    fn reports_unclosed_block() {
+        let err = error("model User {\n  id Int @id\n");
+
+        assert_eq!(err.message, "expected `}`, found end of file");
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+
+    #[test]
+    fn reports_duplicate_fields() {
+        let err = error("model User {\n  id Int @id\n  id String\n}\n");
+
+        assert_eq!(err.message, "field `id` is defined more than once");
+        assert_eq!((err.line, err.column), (3, 3));
+    }
 }
@@ -0,0 +1,354 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::parser::get_schemas;
+use crate::tsconfig::TSCONFIG_FILE;
+
+const PRISMA_CONFIG_FILES: [&str; 4] = [
+    "prisma.config.ts",
+    "prisma.config.mts",
+    "prisma.config.js",
+    "prisma.config.mjs",
+];
+
+#[derive(Debug, Deserialize)]
+struct PackageJsonPrisma {
+    schema: Option<String>,
+}
+
+/// `workspaces` of package.json, either a list of globs or Yarn's object form.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PackageJsonWorkspaces {
+    Patterns(Vec<String>),
+    Config { packages: Vec<String> },
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageJson {
+    name: Option<String>,
+    prisma: Option<PackageJsonPrisma>,
+    workspaces: Option<PackageJsonWorkspaces>,
+}
+
+fn read_package_json(dir: &Path) -> Option<PackageJson> {
+    let content = fs::read_to_string(dir.join("package.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Walks up from `start` looking for the closest directory that looks like the
+/// root of a node project, falling back to `start` itself.
+pub fn find_project_root(start: &Path) -> PathBuf {
+    start
+        .ancestors()
+        .find(|dir| {
+            dir.join("package.json").is_file()
+                || dir.join("prisma").is_dir()
+                || PRISMA_CONFIG_FILES
+                    .iter()
+                    .any(|config| dir.join(config).is_file())
+        })
+        .unwrap_or(start)
+        .to_path_buf()
+}
+
+fn schema_from_package_json(root: &Path) -> Option<PathBuf> {
+    read_package_json(root)?
+        .prisma
+        .and_then(|prisma| prisma.schema)
+        .map(|schema| root.join(schema))
+}
+
+/// Only literal values such as `schema: "prisma/schema.prisma"` are understood,
+/// anything computed at runtime is ignored.
+fn schema_from_prisma_config(root: &Path) -> Option<PathBuf> {
+    PRISMA_CONFIG_FILES.iter().find_map(|config| {
+        let content = fs::read_to_string(root.join(config)).ok()?;
+        let (_, rest) = content.split_once("schema:")?;
+        let rest = rest.trim_start();
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\'' | '`'))?;
+        let (schema, _) = rest[1..].split_once(quote)?;
+
+        Some(root.join(schema))
+    })
+}
+
+/// Resolves where the prisma schema lives, in order of precedence: the
+/// `--schema` argument, `prisma.schema` in package.json, `schema` in
+/// prisma.config.ts, then prisma's default `prisma/` and `schema.prisma`.
+pub fn resolve_schema_path(root: &Path, cli_schema: Option<&Path>) -> PathBuf {
+    if let Some(schema) = cli_schema {
+        return schema.to_path_buf();
+    }
+
+    schema_from_package_json(root)
+        .or_else(|| schema_from_prisma_config(root))
+        .unwrap_or_else(|| {
+            let prisma_dir = root.join("prisma");
+            if prisma_dir.exists() {
+                prisma_dir
+            } else {
+                root.join("schema.prisma")
+            }
+        })
+}
+
+/// Directories never searched for workspace packages.
+fn is_ignored_dir(name: &str) -> bool {
+    name.starts_with('.') || matches!(name, "node_modules" | "dist" | "build")
+}
+
+fn subdirectories(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|entry| Some((entry.file_name().into_string().ok()?, entry.path())))
+        .filter(|(name, _)| !is_ignored_dir(name))
+        .collect()
+}
+
+/// Matches a single path segment against a pattern with `*` and `?`.
+fn matches_wildcard(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            matches_wildcard(&pattern[1..], name)
+                || (!name.is_empty() && matches_wildcard(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => matches_wildcard(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => matches_wildcard(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// Expands the globs used by workspace configs, such as `apps/*` or
+/// `libs/**`, into the directories they match.
+fn expand_glob(dir: &Path, segments: &[&str], found: &mut Vec<PathBuf>) {
+    match segments {
+        [] => found.push(dir.to_path_buf()),
+        ["**", rest @ ..] => {
+            expand_glob(dir, rest, found);
+            for (_, path) in subdirectories(dir) {
+                expand_glob(&path, segments, found);
+            }
+        }
+        [segment, rest @ ..] if segment.contains(['*', '?']) => {
+            for (name, path) in subdirectories(dir) {
+                if matches_wildcard(segment.as_bytes(), name.as_bytes()) {
+                    expand_glob(&path, rest, found);
+                }
+            }
+        }
+        [segment, rest @ ..] => {
+            let path = dir.join(segment);
+            if path.is_dir() {
+                expand_glob(&path, rest, found);
+            }
+        }
+    }
+}
+
+/// Reads the `packages` list of pnpm-workspace.yaml. Only the plain block list
+/// form used in practice is understood.
+fn pnpm_patterns(root: &Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(root.join("pnpm-workspace.yaml")).ok()?;
+    let mut lines = content
+        .lines()
+        .skip_while(|line| line.trim_end() != "packages:")
+        .skip(1);
+
+    let mut patterns = Vec::new();
+    while let Some(item) = lines.next().and_then(|line| line.trim().strip_prefix('-')) {
+        let item = item.split(" #").next().unwrap_or(item).trim();
+        patterns.push(item.trim_matches(['\'', '"']).to_string());
+    }
+
+    Some(patterns)
+}
+
+/// Nx projects are the directories holding a project.json.
+fn nx_projects(dir: &Path, found: &mut Vec<PathBuf>) {
+    for (_, path) in subdirectories(dir) {
+        if path.join("project.json").is_file() {
+            found.push(path.clone());
+        }
+        nx_projects(&path, found);
+    }
+}
+
+/// A pnpm, Nx, npm or Yarn workspace and the directories of its packages.
+#[derive(Debug)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub packages: Vec<PathBuf>,
+}
+
+impl Workspace {
+    /// Walks up from `start` looking for the closest workspace root.
+    pub fn find(start: &Path) -> Option<Workspace> {
+        start.ancestors().find_map(|root| {
+            let is_nx = root.join("nx.json").is_file();
+            let patterns =
+                pnpm_patterns(root).or_else(|| match read_package_json(root)?.workspaces? {
+                    PackageJsonWorkspaces::Patterns(patterns) => Some(patterns),
+                    PackageJsonWorkspaces::Config { packages } => Some(packages),
+                });
+
+            if patterns.is_none() && !is_nx {
+                return None;
+            }
+
+            let expand = |pattern: &str| {
+                let mut found = Vec::new();
+                let segments: Vec<&str> = pattern
+                    .trim_start_matches("./")
+                    .split('/')
+                    .filter(|segment| !segment.is_empty())
+                    .collect();
+                expand_glob(root, &segments, &mut found);
+                found
+            };
+
+            let patterns = patterns.unwrap_or_default();
+            let mut packages: Vec<PathBuf> = patterns
+                .iter()
+                .filter(|pattern| !pattern.starts_with('!'))
+                .flat_map(|pattern| expand(pattern))
+                .filter(|package| package != root && package.join("package.json").is_file())
+                .collect();
+
+            if is_nx {
+                nx_projects(root, &mut packages);
+            }
+
+            let excluded: Vec<PathBuf> = patterns
+                .iter()
+                .filter_map(|pattern| pattern.strip_prefix('!'))
+                .flat_map(expand)
+                .collect();
+
+            packages.retain(|package| !excluded.contains(package));
+            packages.sort();
+            packages.dedup();
+
+            Some(Workspace {
+                root: root.to_path_buf(),
+                packages,
+            })
+        })
+    }
+
+    /// The root and the packages that hold a prisma schema.
+    pub fn schema_packages(&self) -> Vec<&Path> {
+        [self.root.as_path()]
+            .into_iter()
+            .chain(self.packages.iter().map(PathBuf::as_path))
+            .filter(|package| {
+                get_schemas(&resolve_schema_path(package, None))
+                    .is_ok_and(|schemas| !schemas.is_empty())
+            })
+            .collect()
+    }
+
+    /// The root and the packages that have their own tsconfig.json.
+    pub fn tsconfig_packages(&self) -> Vec<&Path> {
+        [self.root.as_path()]
+            .into_iter()
+            .chain(self.packages.iter().map(PathBuf::as_path))
+            .filter(|package| package.join(TSCONFIG_FILE).is_file())
+            .collect()
+    }
+
+    /// The package's path relative to the workspace root, with its name from
+    /// package.json when it has one.
+    pub fn label(&self, package: &Path) -> String {
+        let path = match package.strip_prefix(&self.root) {
+            Ok(path) if path.as_os_str().is_empty() => ".".to_string(),
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(_) => package.to_string_lossy().to_string(),
+        };
+
+        match read_package_json(package).and_then(|package_json| package_json.name) {
+            Some(name) => format!("{} ({})", path, name),
+            None => path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn matches_wildcards_within_a_segment() {
+        let matches =
+            |pattern: &str, name: &str| matches_wildcard(pattern.as_bytes(), name.as_bytes());
+
+        assert!(matches("*", "api"));
+        assert!(matches("*", ""));
+        assert!(matches("app-*", "app-web"));
+        assert!(matches("*-service", "users-service"));
+        assert!(matches("lib?", "lib1"));
+        assert!(matches("a*b*c", "aXbYc"));
+        assert!(!matches("app-*", "web"));
+        assert!(!matches("lib?", "lib"));
+        assert!(!matches("api", "apis"));
+    }
+
+    #[test]
+    fn reads_pnpm_workspace_packages() {
+        let dir = TempDir::new(
+            "pnpm-patterns",
+            &[(
+                "pnpm-workspace.yaml",
+                "# workspace\npackages:\n  - 'apps/*'\n  - \"libs/**\" # shared\n  - '!libs/legacy'\ncatalog:\n  - ignored\n",
+            )],
+        );
+
+        assert_eq!(
+            pnpm_patterns(&dir).unwrap(),
+            ["apps/*", "libs/**", "!libs/legacy"]
+        );
+        assert_eq!(pnpm_patterns(&dir.join("apps")), None);
+    }
+
+    #[test]
+    fn finds_workspace_packages() {
+        let dir = TempDir::new(
+            "pnpm-workspace",
+            &[
+                (
+                    "pnpm-workspace.yaml",
+                    "packages:\n  - apps/*\n  - libs/**\n  - '!libs/legacy'\n",
+                ),
+                ("apps/api/package.json", "{ \"name\": \"api\" }"),
+                ("apps/docs/README.md", ""),
+                ("libs/db/package.json", "{}"),
+                ("libs/db/src/index.ts", ""),
+                ("libs/legacy/package.json", "{}"),
+                ("libs/node_modules/dep/package.json", "{}"),
+            ],
+        );
+
+        let workspace = Workspace::find(&dir.join("apps/api/src")).unwrap();
+        assert_eq!(workspace.root, *dir);
+        assert_eq!(
+            workspace.packages,
+            [dir.join("apps/api"), dir.join("libs/db")]
+        );
+        assert_eq!(workspace.label(&dir.join("apps/api")), "apps/api (api)");
+        assert_eq!(workspace.label(&dir), ".");
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs,
+    path::Path,
+};
+
+use crate::barrel::remove_export;
+use crate::code_gen::ModuleType;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::manifest::Manifest;
+use crate::nest::unregister_repository;
+use crate::parser::Schema;
+
+/// Deletes the generated files of models, views and composite types that no
+/// longer exist in the schema, along with their barrel exports and their
+/// registration in the database module. Files modified by hand are only
+/// deleted with `force`, and with `dry_run` the files are only reported.
+pub fn prune(
+    dir: &Path,
+    schema: &Schema,
+    manifest: &mut Manifest,
+    config: &Config,
+    force: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let sources: HashSet<&str> = schema
+        .entities()
+        .map(|model| model.name.as_str())
+        .chain(schema.types.iter().map(|composite| composite.name.as_str()))
+        .collect();
+
+    let stale: Vec<String> = manifest
+        .files
+        .iter()
+        .filter(|(_, entry)| {
+            entry
+                .model
+                .as_deref()
+                .is_some_and(|model| !sources.contains(model))
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    if stale.is_empty() {
+        println!("nothing to prune");
+    }
+
+    // Models whose repository is gone, or would be with `dry_run`.
+    let mut unregistered = BTreeSet::new();
+
+    for key in stale {
+        let path = manifest.path(&key);
+        if let Some(entry) = manifest.files.get(&key) {
+            let is_repository = entry.artifact == ModuleType::Repository(None).label();
+            let is_kept = path.exists() && manifest.is_modified(&path) && !force;
+            if is_repository && !is_kept {
+                unregistered.extend(entry.model.clone());
+            }
+        }
+
+        if !path.exists() {
+            println!("{} (already removed)", key);
+        } else if manifest.is_modified(&path) && !force {
+            println!("{} (modified by hand, kept; use --force to delete it)", key);
+            continue;
+        } else if dry_run {
+            println!("{} (would be deleted)", key);
+            continue;
+        } else {
+            fs::remove_file(&path).map_err(Error::io(&path))?;
+            println!("{} (deleted)", key);
+        }
+
+        if !dry_run {
+            remove_export(&path).map_err(Error::io(&path))?;
+        }
+
+        manifest.files.remove(&key);
+    }
+
+    for model in &unregistered {
+        unregister_repository(dir, config, model, dry_run)?;
+    }
+
+    if !dry_run {
+        manifest.save()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, io};
+
+fn region_start(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix("// <custom:")?
+        .strip_suffix('>')
+        .filter(|name| !name.starts_with('/'))
+}
+
+fn region_end(line: &str) -> Option<&str> {
+    line.trim().strip_prefix("// </custom:")?.strip_suffix('>')
+}
+
+/// Collects the lines between every `// <custom:name>` and `// </custom:name>`
+/// pair, keyed by region name. A region left open at the end of the file is
+/// an error, since there is no telling where its hand-written lines end.
+pub fn extract_regions(contents: &str) -> io::Result<HashMap<&str, Vec<&str>>> {
+    let mut regions = HashMap::new();
+    let mut current: Option<(&str, usize, Vec<&str>)> = None;
+
+    for (number, line) in contents.lines().enumerate() {
+        match current.take() {
+            Some((name, _, lines)) if region_end(line) == Some(name) => {
+                regions.insert(name, lines);
+            }
+            Some((name, start, mut lines)) => {
+                lines.push(line);
+                current = Some((name, start, lines));
+            }
+            None => current = region_start(line).map(|name| (name, number + 1, Vec::new())),
+        }
+    }
+
+    if let Some((name, start, _)) = current {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "custom region `{}` opened on line {} is never closed, add `// </custom:{}>` before regenerating",
+                name, start, name
+            ),
+        ));
+    }
+
+    Ok(regions)
+}
+
+/// Carries the hand-written contents of the protected regions of `existing`
+/// over into the freshly `generated` file. Regions that no longer exist in the
+/// generated file are reported and dropped. Fails when `existing` has a region
+/// that is never closed rather than losing the lines after it.
+pub fn merge_regions(existing: &str, generated: &str) -> io::Result<String> {
+    let mut preserved = extract_regions(existing)?;
+    let mut merged = Vec::new();
+    let mut skipping: Option<&str> = None;
+
+    for line in generated.lines() {
+        if let Some(name) = skipping {
+            if region_end(line) != Some(name) {
+                continue;
+            }
+
+            skipping = None;
+        }
+
+        merged.push(line);
+
+        if let Some(name) = region_start(line) {
+            if let Some(lines) = preserved.remove(name) {
+                merged.extend(lines);
+                skipping = Some(name);
+            }
+        }
+    }
+
+    for (name, lines) in preserved {
+        if lines.iter().any(|line| !line.trim().is_empty()) {
+            eprintln!(
+                "warning: dropping custom region `{}`, the generated file no longer has it",
+                name
+            );
+        }
+    }
+
+    let mut merged = merged.join("\n");
+    if generated.ends_with('\n') {
+        merged.push('\n');
+    }
+
+    Ok(merged)
+}
+
+/// Empties every protected region, leaving only the generated parts of a file.
+pub fn strip_regions(contents: &str) -> String {
+    let mut stripped = Vec::new();
+    let mut current: Option<&str> = None;
+
+    for line in contents.lines() {
+        match current {
+            Some(name) if region_end(line) != Some(name) => continue,
+            Some(_) => current = None,
+            None => current = region_start(line),
+        }
+
+        stripped.push(line);
+    }
+
+    let mut stripped = stripped.join("\n");
+    if contents.ends_with('\n') {
+        stripped.push('\n');
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_custom_lines_and_takes_generated_ones() {
+        let existing = "\
+// <custom:imports>
+import { Foo } from './foo'
+// </custom:imports>
+export class User {
+\told: string
+\t// <custom:methods>
+\tgreet() {}
+\t// </custom:methods>
+}
+";
+        let generated = "\
+// <custom:imports>
+// </custom:imports>
+export class User {
+\tname: string
+\t// <custom:methods>
+\t// </custom:methods>
+}
+";
+
+        assert_eq!(
+            merge_regions(existing, generated).unwrap(),
+            "\
+// <custom:imports>
+import { Foo } from './foo'
+// </custom:imports>
+export class User {
+\tname: string
+\t// <custom:methods>
+\tgreet() {}
+\t// </custom:methods>
+}
+"
+        );
+    }
+
+    #[test]
+    fn new_regions_stay_empty_and_removed_ones_are_dropped() {
+        let existing = "// <custom:gone>\nkept()\n// </custom:gone>\n";
+        let generated = "// <custom:methods>\n// </custom:methods>";
+
+        assert_eq!(merge_regions(existing, generated).unwrap(), generated);
+    }
+
+    #[test]
+    fn refuses_to_merge_a_region_left_open() {
+        let existing = "class A {\n\t// <custom:methods>\n\tgreet() {}\n}\n";
+        let generated = "class A {\n\t// <custom:methods>\n\t// </custom:methods>\n}\n";
+
+        let err = merge_regions(existing, generated).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err
+            .to_string()
+            .starts_with("custom region `methods` opened on line 2 is never closed"));
+    }
+
+    #[test]
+    fn strips_region_contents() {
+        assert_eq!(
+            strip_regions("a\n// <custom:x>\nb\n// </custom:x>\nc\n"),
+            "a\n// <custom:x>\n// </custom:x>\nc\n"
+        );
+    }
+}
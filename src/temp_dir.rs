@@ -0,0 +1,41 @@
+use std::{
+    env, fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// A directory for tests, removed again when dropped, including when an
+/// assertion fails.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// A fresh directory with the given files, creating their parents. `name`
+    /// keeps the directories of tests running in parallel apart.
+    pub fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let dir = env::temp_dir().join(format!("entity-generator-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        TempDir(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,74 @@
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+use minijinja::Environment;
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+
+pub const ENTITY_TEMPLATE: &str = "entity.ts.jinja";
+pub const VALUE_OBJECT_TEMPLATE: &str = "value-object.ts.jinja";
+pub const MAPPER_TEMPLATE: &str = "mapper.ts.jinja";
+pub const REPOSITORY_TEMPLATE: &str = "repository.ts.jinja";
+pub const PRISMA_REPOSITORY_TEMPLATE: &str = "prisma-repository.ts.jinja";
+pub const DATABASE_MODULE_TEMPLATE: &str = "database-module.ts.jinja";
+
+const BUILTIN_TEMPLATES: [(&str, &str); 6] = [
+    (ENTITY_TEMPLATE, include_str!("templates/entity.ts.jinja")),
+    (
+        VALUE_OBJECT_TEMPLATE,
+        include_str!("templates/value-object.ts.jinja"),
+    ),
+    (MAPPER_TEMPLATE, include_str!("templates/mapper.ts.jinja")),
+    (
+        REPOSITORY_TEMPLATE,
+        include_str!("templates/repository.ts.jinja"),
+    ),
+    (
+        PRISMA_REPOSITORY_TEMPLATE,
+        include_str!("templates/prisma-repository.ts.jinja"),
+    ),
+    (
+        DATABASE_MODULE_TEMPLATE,
+        include_str!("templates/database-module.ts.jinja"),
+    ),
+];
+
+/// Renders the generated files, preferring templates found in the project's
+/// templates directory over the built-in ones.
+pub struct Templates {
+    env: Environment<'static>,
+}
+
+impl Templates {
+    pub fn new(templates_dir: PathBuf) -> Self {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_loader(
+            move |name| match fs::read_to_string(templates_dir.join(name)) {
+                Ok(content) => Ok(Some(content)),
+                Err(err) if err.kind() == ErrorKind::NotFound => Ok(BUILTIN_TEMPLATES
+                    .iter()
+                    .find(|(builtin, _)| *builtin == name)
+                    .map(|(_, content)| content.to_string())),
+                Err(err) => Err(minijinja::Error::new(
+                    minijinja::ErrorKind::InvalidOperation,
+                    format!("failed to read template {}", name),
+                )
+                .with_source(err)),
+            },
+        );
+
+        Templates { env }
+    }
+
+    pub fn render<S: Serialize>(&self, name: &str, context: S) -> Result<String> {
+        self.env
+            .get_template(name)
+            .and_then(|template| template.render(context))
+            .map_err(|err| Error::Template {
+                name: name.to_string(),
+                message: format!("{:#}", err),
+            })
+    }
+}
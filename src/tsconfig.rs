@@ -0,0 +1,596 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::error::Error;
+
+pub const TSCONFIG_FILE: &str = "tsconfig.json";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCompilerOptions {
+    base_url: Option<String>,
+    paths: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Extends {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTsConfig {
+    extends: Option<Extends>,
+    #[serde(default)]
+    compiler_options: RawCompilerOptions,
+}
+
+#[derive(Debug, Default)]
+pub struct TsConfigCompilerOptions {
+    /// Absolute, resolved against the config file that declares it.
+    pub base_url: Option<PathBuf>,
+    pub paths: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct TsConfig {
+    pub compiler_options: TsConfigCompilerOptions,
+    /// Directory of the config file declaring `paths`, which they are relative
+    /// to when there is no `baseUrl`.
+    paths_dir: PathBuf,
+    /// Project root that module paths are made relative to.
+    root: PathBuf,
+}
+
+/// The compiler options collected while following `extends`, before falling
+/// back to the defaults.
+#[derive(Default)]
+struct Resolved {
+    base_url: Option<PathBuf>,
+    paths: Option<(HashMap<String, Vec<String>>, PathBuf)>,
+}
+
+/// Resolves `.` and `..` components without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+fn existing_config(path: PathBuf) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path);
+    }
+
+    let mut with_extension = path.clone().into_os_string();
+    with_extension.push(".json");
+    let with_extension = PathBuf::from(with_extension);
+    if with_extension.is_file() {
+        return Some(with_extension);
+    }
+
+    let tsconfig = path.join(TSCONFIG_FILE);
+    tsconfig.is_file().then_some(tsconfig)
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+    tsconfig: Option<String>,
+}
+
+/// Finds the config an `extends` entry refers to: a path relative to the
+/// extending file, or a package (or a file inside one) in `node_modules`.
+fn resolve_extends(spec: &str, dir: &Path) -> Option<PathBuf> {
+    if spec.starts_with('.') || Path::new(spec).is_absolute() {
+        return existing_config(dir.join(spec));
+    }
+
+    dir.ancestors().find_map(|ancestor| {
+        let package = ancestor.join("node_modules").join(spec);
+        let tsconfig = fs::read_to_string(package.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<PackageJson>(&content).ok())
+            .and_then(|package_json| package_json.tsconfig);
+
+        match tsconfig {
+            Some(tsconfig) => existing_config(package.join(tsconfig)),
+            None => existing_config(package),
+        }
+    })
+}
+
+/// Turns the JSON with comments accepted by TypeScript into plain JSON by
+/// blanking out comments and dropping trailing commas. Line breaks are kept so
+/// that error locations still match the original file.
+fn strip_jsonc(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    // Position of a comma that is only kept if more than whitespace follows.
+    let mut pending_comma: Option<usize> = None;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                pending_comma = None;
+                output.push(c);
+                while let Some(c) = chars.next() {
+                    output.push(c);
+                    match c {
+                        '\\' => output.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            ',' => {
+                pending_comma = Some(output.len());
+                output.push(c);
+            }
+            '}' | ']' => {
+                if let Some(position) = pending_comma.take() {
+                    output.replace_range(position..position + 1, " ");
+                }
+                output.push(c);
+            }
+            c if c.is_whitespace() => output.push(c),
+            c => {
+                pending_comma = None;
+                output.push(c);
+            }
+        }
+    }
+
+    output
+}
+
+impl TsConfig {
+    pub fn default(root: &Path) -> Self {
+        let mut paths = HashMap::new();
+        paths.insert("@src/*".to_string(), Vec::from(["src/*".to_string()]));
+        TsConfig {
+            compiler_options: TsConfigCompilerOptions {
+                base_url: None,
+                paths,
+            },
+            paths_dir: root.to_path_buf(),
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// Reads a config file and the ones it extends, later files overriding
+    /// the options of earlier ones.
+    fn read(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Resolved, Error> {
+        let path = normalize(path);
+        let invalid = |message: String| Error::TsConfigInvalid {
+            path: path.clone(),
+            message,
+        };
+
+        if chain.contains(&path) {
+            return Err(invalid("circular extends".to_string()));
+        }
+
+        let content = fs::read_to_string(&path).map_err(Error::io(&path))?;
+        let raw: RawTsConfig =
+            serde_json::from_str(&strip_jsonc(&content)).map_err(|err| invalid(err.to_string()))?;
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        chain.push(path.clone());
+
+        let extends = match raw.extends {
+            Some(Extends::One(spec)) => vec![spec],
+            Some(Extends::Many(specs)) => specs,
+            None => Vec::new(),
+        };
+
+        let mut resolved = Resolved::default();
+        for spec in extends {
+            let parent = resolve_extends(&spec, &dir)
+                .ok_or_else(|| invalid(format!("cannot resolve extends {}", spec)))?;
+            let parent = TsConfig::read(&parent, chain)?;
+
+            resolved.base_url = parent.base_url.or(resolved.base_url);
+            resolved.paths = parent.paths.or(resolved.paths);
+        }
+
+        chain.pop();
+
+        let options = raw.compiler_options;
+        if let Some(base_url) = options.base_url {
+            resolved.base_url = Some(normalize(&dir.join(base_url)));
+        }
+        if let Some(paths) = options.paths {
+            resolved.paths = Some((paths, dir));
+        }
+
+        Ok(resolved)
+    }
+
+    /// Reads the project's tsconfig, which may contain comments and trailing
+    /// commas, following `extends`. Without a tsconfig or path aliases the
+    /// default `@src/*` module is used.
+    pub fn load(dir: &Path) -> Result<Self, Error> {
+        let path = dir.join(TSCONFIG_FILE);
+        if !path.exists() {
+            eprintln!(
+                "warning: no {} found in {}, using the default @src/* module",
+                TSCONFIG_FILE,
+                dir.display()
+            );
+            return Ok(TsConfig::default(dir));
+        }
+
+        let resolved = TsConfig::read(&path, &mut Vec::new())?;
+        let Some((paths, paths_dir)) = resolved.paths else {
+            eprintln!(
+                "warning: {} has no compilerOptions.paths, using the default @src/* module",
+                path.display()
+            );
+            return Ok(TsConfig::default(dir));
+        };
+
+        Ok(TsConfig {
+            compiler_options: TsConfigCompilerOptions {
+                base_url: resolved.base_url,
+                paths,
+            },
+            paths_dir,
+            root: dir.to_path_buf(),
+        })
+    }
+
+    /// Output directory of an alias target: the part before the wildcard, or
+    /// the parent directory when the target is a file or a partial name.
+    fn target_dir(&self, target: &str) -> String {
+        let (prefix, wildcard) = match target.split_once('*') {
+            Some((prefix, _)) => (prefix, true),
+            None => (target, false),
+        };
+
+        let mut path = PathBuf::from(prefix);
+        let is_partial = wildcard && !prefix.is_empty() && !prefix.ends_with('/');
+        let is_file = !wildcard && path.extension().is_some();
+        if is_partial || is_file {
+            path.pop();
+        }
+
+        let base = self
+            .compiler_options
+            .base_url
+            .as_deref()
+            .unwrap_or(&self.paths_dir);
+        let path = normalize(&base.join(path));
+
+        path.strip_prefix(&self.root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Every path alias with at least one target, sorted by alias.
+    pub fn modules(&self) -> Vec<Module> {
+        let mut modules: Vec<Module> = self
+            .compiler_options
+            .paths
+            .iter()
+            .filter_map(|(alias, targets)| {
+                let mut dirs: Vec<String> = Vec::new();
+                for dir in targets.iter().map(|target| self.target_dir(target)) {
+                    if !dirs.contains(&dir) {
+                        dirs.push(dir);
+                    }
+                }
+
+                (!dirs.is_empty()).then(|| Module {
+                    alias: alias.clone(),
+                    dirs,
+                })
+            })
+            .collect();
+
+        modules.sort_by(|a, b| a.alias.cmp(&b.alias));
+        modules
+    }
+
+    /// Looks up a module by its alias or by its name.
+    pub fn module(&self, name: &str) -> Option<Module> {
+        self.modules()
+            .into_iter()
+            .find(|module| module.alias == name || module.name() == name)
+    }
+}
+
+/// A tsconfig path alias and the directories its targets resolve to, relative
+/// to the project root when inside of it.
+#[derive(Debug)]
+pub struct Module {
+    pub alias: String,
+    pub dirs: Vec<String>,
+}
+
+impl Module {
+    /// The alias without wildcard and prefix character, `users` for
+    /// `@users/*`.
+    pub fn name(&self) -> &str {
+        let name = self.alias.trim_end_matches('*').trim_end_matches('/');
+        match name.trim_start_matches(['@', '~', '#']) {
+            "" => name,
+            trimmed => trimmed,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}  →  {}", self.alias, self.dirs.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn strips_comments() {
+        let source = "{\n  // line\n  \"a\": 1, /* block\n  comment */ \"b\": 2\n}";
+        let stripped = strip_jsonc(source);
+
+        assert_eq!(stripped.lines().count(), source.lines().count());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&stripped).unwrap(),
+            serde_json::json!({ "a": 1, "b": 2 })
+        );
+    }
+
+    #[test]
+    fn drops_trailing_commas() {
+        let stripped = strip_jsonc("{ \"paths\": { \"@a/*\": [\"src/a/*\",], }, }");
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&stripped).unwrap(),
+            serde_json::json!({ "paths": { "@a/*": ["src/a/*"] } })
+        );
+    }
+
+    #[test]
+    fn keeps_strings_intact() {
+        let source = r#"{ "url": "https://example.com/*,}", "quote": "a \" // b" }"#;
+
+        assert_eq!(strip_jsonc(source), source);
+    }
+
+    fn ts_config(base_url: Option<&str>, paths_dir: &str) -> TsConfig {
+        TsConfig {
+            compiler_options: TsConfigCompilerOptions {
+                base_url: base_url.map(PathBuf::from),
+                paths: HashMap::new(),
+            },
+            paths_dir: PathBuf::from(paths_dir),
+            root: PathBuf::from("/project"),
+        }
+    }
+
+    #[test]
+    fn resolves_alias_targets_to_directories() {
+        let config = ts_config(None, "/project");
+
+        assert_eq!(config.target_dir("src/users/*"), "src/users");
+        assert_eq!(config.target_dir("./src/users/*"), "src/users");
+        assert_eq!(config.target_dir("src/users/user-*"), "src/users");
+        assert_eq!(config.target_dir("src/domain/index.ts"), "src/domain");
+        assert_eq!(config.target_dir("src/lib"), "src/lib");
+    }
+
+    #[test]
+    fn resolves_alias_targets_against_base_url_or_declaring_file() {
+        assert_eq!(
+            ts_config(Some("/project/src"), "/project").target_dir("users/*"),
+            "src/users"
+        );
+        assert_eq!(
+            ts_config(None, "/project/config").target_dir("../libs/shared/*"),
+            "libs/shared"
+        );
+        assert_eq!(
+            ts_config(None, "/project").target_dir("../outside/*"),
+            "/outside"
+        );
+    }
+
+    #[test]
+    fn lists_modules_by_alias_and_name() {
+        let mut config = ts_config(None, "/project");
+        config.compiler_options.paths = HashMap::from([
+            (
+                "@users/*".to_string(),
+                vec!["src/users/*".to_string(), "src/users/*".to_string()],
+            ),
+            (
+                "#domain".to_string(),
+                vec!["src/domain/index.ts".to_string(), "libs/domain".to_string()],
+            ),
+            ("@empty/*".to_string(), Vec::new()),
+        ]);
+
+        let modules = config.modules();
+        let aliases: Vec<&str> = modules.iter().map(|module| module.alias.as_str()).collect();
+        assert_eq!(aliases, ["#domain", "@users/*"]);
+        assert_eq!(modules[0].dirs, ["src/domain", "libs/domain"]);
+        assert_eq!(modules[1].dirs, ["src/users"]);
+
+        assert_eq!(config.module("users").unwrap().alias, "@users/*");
+        assert_eq!(config.module("#domain").unwrap().name(), "domain");
+        assert!(config.module("posts").is_none());
+    }
+
+    fn aliases(config: &TsConfig) -> Vec<&str> {
+        let mut aliases: Vec<&str> = config
+            .compiler_options
+            .paths
+            .keys()
+            .map(String::as_str)
+            .collect();
+        aliases.sort();
+        aliases
+    }
+
+    #[test]
+    fn later_files_in_an_extends_chain_override_earlier_ones() {
+        let dir = TempDir::new(
+            "tsconfig-chain",
+            &[
+                (
+                    "tsconfig.json",
+                    r#"{ "extends": "./config/base", "compilerOptions": { "paths": { "@app/*": ["src/app/*"] } } }"#,
+                ),
+                (
+                    "config/base.json",
+                    r#"{ "extends": "./root.json", "compilerOptions": { "baseUrl": "..", "paths": { "@base/*": ["base/*"] } } }"#,
+                ),
+                (
+                    "config/root.json",
+                    r#"{ "compilerOptions": { "baseUrl": "./root", "paths": { "@root/*": ["root/*"] } } }"#,
+                ),
+            ],
+        );
+
+        let config = TsConfig::load(&dir).unwrap();
+        assert_eq!(aliases(&config), ["@app/*"]);
+        assert_eq!(config.paths_dir, *dir);
+        assert_eq!(config.compiler_options.base_url.as_deref(), Some(&*dir));
+    }
+
+    #[test]
+    fn later_entries_of_an_extends_array_override_earlier_ones() {
+        let dir = TempDir::new(
+            "tsconfig-array",
+            &[
+                (
+                    "tsconfig.json",
+                    r#"{ "extends": ["./a", "./b/tsconfig.json"] }"#,
+                ),
+                (
+                    "a/tsconfig.json",
+                    r#"{ "compilerOptions": { "baseUrl": ".", "paths": { "@a/*": ["*"] } } }"#,
+                ),
+                (
+                    "b/tsconfig.json",
+                    r#"{ "compilerOptions": { "paths": { "@b/*": ["src/*"] } } }"#,
+                ),
+            ],
+        );
+
+        let config = TsConfig::load(&dir).unwrap();
+        assert_eq!(aliases(&config), ["@b/*"]);
+        assert_eq!(config.paths_dir, dir.join("b"));
+        assert_eq!(config.compiler_options.base_url, Some(dir.join("a")));
+    }
+
+    #[test]
+    fn inherits_base_url_from_an_extended_file() {
+        let dir = TempDir::new(
+            "tsconfig-base-url",
+            &[
+                (
+                    "tsconfig.json",
+                    r#"{
+                        // The aliases are resolved against the inherited baseUrl.
+                        "extends": "./config/base.json",
+                        "compilerOptions": { "paths": { "@users/*": ["users/*"], }, },
+                    }"#,
+                ),
+                (
+                    "config/base.json",
+                    r#"{ "compilerOptions": { "baseUrl": "../src" } }"#,
+                ),
+            ],
+        );
+
+        let config = TsConfig::load(&dir).unwrap();
+        assert_eq!(config.compiler_options.base_url, Some(dir.join("src")));
+        assert_eq!(config.modules()[0].dirs, ["src/users"]);
+    }
+
+    #[test]
+    fn extends_packages_from_node_modules() {
+        let dir = TempDir::new(
+            "tsconfig-packages",
+            &[
+                (
+                    "apps/api/tsconfig.json",
+                    r#"{ "extends": ["@tsconfig/node20", "shared-config", "shared-config/paths.json"] }"#,
+                ),
+                (
+                    "node_modules/@tsconfig/node20/tsconfig.json",
+                    r#"{ "compilerOptions": { "baseUrl": "." } }"#,
+                ),
+                (
+                    "node_modules/shared-config/package.json",
+                    r#"{ "name": "shared-config", "tsconfig": "./configs/strict.json" }"#,
+                ),
+                (
+                    "node_modules/shared-config/configs/strict.json",
+                    r#"{ "compilerOptions": { "baseUrl": "../src" } }"#,
+                ),
+                (
+                    "node_modules/shared-config/paths.json",
+                    r#"{ "compilerOptions": { "paths": { "@shared/*": ["*"] } } }"#,
+                ),
+            ],
+        );
+
+        let config = TsConfig::load(&dir.join("apps/api")).unwrap();
+        assert_eq!(aliases(&config), ["@shared/*"]);
+        assert_eq!(
+            config.compiler_options.base_url,
+            Some(dir.join("node_modules/shared-config/src"))
+        );
+        assert_eq!(config.paths_dir, dir.join("node_modules/shared-config"));
+    }
+
+    #[test]
+    fn rejects_circular_extends() {
+        let dir = TempDir::new(
+            "tsconfig-circular",
+            &[
+                ("tsconfig.json", r#"{ "extends": "./base.json" }"#),
+                ("base.json", r#"{ "extends": "./tsconfig.json" }"#),
+            ],
+        );
+
+        let Err(Error::TsConfigInvalid { path, message }) = TsConfig::load(&dir) else {
+            panic!("expected a circular extends error");
+        };
+        assert_eq!(path, dir.join("tsconfig.json"));
+        assert_eq!(message, "circular extends");
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::code_gen::{generate_targets, write_modules, GeneratedFile};
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::manifest::Manifest;
+use crate::nest::register_repositories;
+use crate::parser::{is_schema_file, load_schema};
+use crate::templates::Templates;
+use crate::tsconfig::TsConfig;
+use crate::writer::{file_status, FileStatus, Writer};
+
+/// Editors often save a file in several steps, so events arriving within this
+/// delay of each other are handled as one change.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Parses the schema and renders the configured targets, keyed by path.
+fn generate(
+    dir: &Path,
+    schema_path: &Path,
+    ts_config: &TsConfig,
+    config: &Config,
+    templates: &Templates,
+) -> Result<BTreeMap<PathBuf, GeneratedFile>> {
+    let schema = load_schema(schema_path)?;
+
+    Ok(
+        generate_targets(dir, &schema, ts_config, config, templates)?
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect(),
+    )
+}
+
+fn is_schema_change(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event.paths.iter().any(|path| is_schema_file(path))
+}
+
+/// Regenerates the configured targets whenever a schema file changes. Only the
+/// files whose generated contents changed are written, and files modified by
+/// hand are left alone.
+pub fn watch(
+    dir: &Path,
+    schema_path: &Path,
+    ts_config: &TsConfig,
+    config: &Config,
+    dry_run: bool,
+) -> Result<()> {
+    if config.generate.is_empty() {
+        eprintln!("warning: no `generate` targets configured, nothing to regenerate");
+    }
+
+    let templates = Templates::new(dir.join(&config.templates_dir));
+    let writer = Writer {
+        force: true,
+        dry_run,
+        barrels: config.barrels,
+    };
+
+    let mut previous = generate(dir, schema_path, ts_config, config, &templates)?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+
+    // Watching the parent of a single schema file keeps working when editors
+    // replace the file instead of writing to it. Only that directory is
+    // watched, as it may be the project root.
+    let (watched, mode) = if schema_path.is_file() {
+        (
+            schema_path.parent().unwrap_or(dir),
+            RecursiveMode::NonRecursive,
+        )
+    } else {
+        (schema_path, RecursiveMode::Recursive)
+    };
+    watcher.watch(watched, mode)?;
+
+    println!("watching {} for changes", schema_path.display());
+
+    while let Ok(event) = rx.recv() {
+        let mut changed = event.is_ok_and(|event| is_schema_change(&event));
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            changed |= event.is_ok_and(|event| is_schema_change(&event));
+        }
+
+        if !changed {
+            continue;
+        }
+
+        // Errors are reported without exiting, so that watching continues
+        // once the schema is fixed.
+        let current = match generate(dir, schema_path, ts_config, config, &templates) {
+            Ok(current) => current,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                continue;
+            }
+        };
+
+        // A model is affected when any of its generated files changed. All of
+        // its files are written then, and missing files are always created,
+        // including value objects, which belong to their composite type rather
+        // than to the model that embeds it.
+        let affected: BTreeSet<Option<&str>> = current
+            .values()
+            .filter(|file| {
+                previous
+                    .get(&file.path)
+                    .is_none_or(|old| old.contents != file.contents)
+            })
+            .map(|file| file.model.as_deref())
+            .collect();
+
+        let mut manifest = Manifest::load(dir)?;
+        let mut files = Vec::new();
+
+        // Existing files are only overwritten when the manifest shows they
+        // still hold what the generator wrote.
+        for file in current.values() {
+            match file_status(&file.path, &file.contents) {
+                Ok(FileStatus::Unchanged) => {}
+                Ok(FileStatus::New) => files.push(file.clone()),
+                Err(err) => eprintln!("error: {}", Error::io(&file.path)(err)),
+                _ if !affected.contains(&file.model.as_deref()) => {}
+                _ if !manifest.contains(&file.path) => {
+                    println!("{} (not generated, skipped)", file.path.display());
+                }
+                _ if manifest.is_modified(&file.path) => {
+                    println!("{} (modified by hand, skipped)", file.path.display());
+                }
+                _ => files.push(file.clone()),
+            }
+        }
+
+        if files.is_empty() {
+            println!("schema changed, nothing to write");
+        }
+
+        let written = write_modules(&files, &writer, &mut manifest)?;
+        register_repositories(&written, dir, config, &templates, dry_run)?;
+
+        // With `dry_run` the writer already printed what would change.
+        if !dry_run {
+            for file in &written {
+                println!(
+                    "{} ({})",
+                    file.path.display(),
+                    file.model.as_deref().unwrap_or("schema")
+                );
+            }
+        }
+
+        previous = current;
+    }
+
+    Ok(())
+}
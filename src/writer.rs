@@ -0,0 +1,144 @@
+use std::io::Write as IoWrite;
+use std::{fs, io, path::Path};
+
+use dialoguer::{theme::ColorfulTheme, Select};
+use similar::TextDiff;
+
+use crate::error::{Error, Result};
+use crate::regions::merge_regions;
+
+const OVERWRITE: &str = "Overwrite";
+const SKIP: &str = "Skip";
+const WRITE_NEW: &str = "Write as .new";
+const SHOW_DIFF: &str = "Show diff";
+
+pub fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(
+            &format!("{} (current)", path.display()),
+            &format!("{} (generated)", path.display()),
+        )
+        .to_string()
+}
+
+fn write_to_module<P: AsRef<Path>>(path: P, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.as_ref().parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = fs::File::create(path)?;
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
+
+pub enum FileStatus {
+    New,
+    Unchanged,
+    /// The file differs from the generated contents, which already have the
+    /// custom regions of the existing file merged in.
+    Changed {
+        existing: String,
+        merged: String,
+    },
+}
+
+pub fn file_status(path: &Path, contents: &str) -> io::Result<FileStatus> {
+    let existing = match fs::read_to_string(path) {
+        Ok(existing) => existing,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(FileStatus::New),
+        Err(err) => return Err(err),
+    };
+
+    let merged = merge_regions(&existing, contents)?;
+    if existing == merged {
+        return Ok(FileStatus::Unchanged);
+    }
+
+    Ok(FileStatus::Changed { existing, merged })
+}
+
+/// Writes generated files, asking before replacing a file that already exists
+/// with different contents unless `force` is set. The custom regions of
+/// existing files are always carried over. With `dry_run` nothing is written,
+/// the planned changes are printed instead.
+pub struct Writer {
+    pub force: bool,
+    pub dry_run: bool,
+    /// Export written files from the `index.ts` of their directory.
+    pub barrels: bool,
+}
+
+impl Writer {
+    /// Returns whether the file now holds the generated contents. `modified`
+    /// marks files whose generated parts were edited by hand.
+    pub fn write(&self, path: &Path, contents: &str, modified: bool) -> Result<bool> {
+        let (existing, contents) = match file_status(path, contents).map_err(Error::io(path))? {
+            FileStatus::New if self.dry_run => {
+                println!("{} (new)\n{}", path.display(), contents);
+                return Ok(false);
+            }
+            FileStatus::New => {
+                return write_to_module(path, contents)
+                    .map(|_| true)
+                    .map_err(Error::io(path))
+            }
+            FileStatus::Unchanged => {
+                if self.dry_run {
+                    println!("{} (unchanged)", path.display());
+                }
+
+                return Ok(!self.dry_run);
+            }
+            FileStatus::Changed { existing, merged } => (existing, merged),
+        };
+        let contents = contents.as_str();
+
+        if self.dry_run {
+            print!("{}", unified_diff(path, &existing, contents));
+            return Ok(false);
+        }
+
+        if self.force {
+            return write_to_module(path, contents)
+                .map(|_| true)
+                .map_err(Error::io(path));
+        }
+
+        let prompt = if modified {
+            format!("{} already exists and was modified by hand", path.display())
+        } else {
+            format!("{} already exists", path.display())
+        };
+        let mut options = vec![OVERWRITE, SKIP, WRITE_NEW, SHOW_DIFF];
+
+        loop {
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(&prompt)
+                .default(if modified { 1 } else { 0 })
+                .items(&options)
+                .interact()?;
+
+            match options[selection] {
+                OVERWRITE => {
+                    return write_to_module(path, contents)
+                        .map(|_| true)
+                        .map_err(Error::io(path))
+                }
+                SKIP => return Ok(false),
+                WRITE_NEW => {
+                    let mut new_path = path.as_os_str().to_owned();
+                    new_path.push(".new");
+                    return write_to_module(&new_path, contents)
+                        .map(|_| false)
+                        .map_err(Error::io(new_path));
+                }
+                _ => {
+                    print!("{}", unified_diff(path, &existing, contents));
+                    options.retain(|option| *option != SHOW_DIFF);
+                }
+            }
+        }
+    }
+}